@@ -4,7 +4,16 @@ import warnings
 from collections.abc import Iterable
 from enum import Enum
 from pathlib import Path
-from typing import Any, Literal, Protocol, cast
+from typing import (
+    TYPE_CHECKING,
+    Any,
+    Literal,
+    Protocol,
+    TypedDict,
+    Union,
+    cast,
+    get_args,
+)
 
 import polars as pl
 from polars.plugins import register_plugin_function
@@ -16,9 +25,50 @@ except ImportError:
 
 from polars_hash._internal import __version__ as __version__
 
+if TYPE_CHECKING:
+    # `typing.Unpack` is new in Python 3.11. A type checker has `typing_extensions`.
+    from typing_extensions import Unpack
+
 _PLUGIN_PATH = Path(__file__).parent
 _U64_MAX = 2**64 - 1
 
+NullAs = Union[Literal["propagate", "empty"], bytes]
+"""What a hasher does with a null value. "propagate" gives a null hash, "empty" hashes
+the null as a value with no bytes, and `bytes` hashes it as those bytes."""
+
+InputEncoding = Literal["utf8", "utf16le", "latin1", "hex", "base64"]
+"""The bytes that a hasher reads from a String value. "utf8" is the String itself,
+"utf16le" and "latin1" encode its text again, and "hex" and "base64" decode it."""
+
+OnDecodeError = Literal["raise", "null"]
+"""What a hasher does with a String value that `InputEncoding` cannot give bytes for."""
+
+_INPUT_ENCODINGS = get_args(InputEncoding)
+
+Affix = Union[bytes, str, pl.Expr]
+"""The bytes that a hasher reads before or after each value. A `str` is its UTF-8 bytes,
+and an expression gives other bytes on each row."""
+
+
+class InputKwargs(TypedDict, total=False):
+    """The kwargs of the input column, which every hasher takes. A hasher takes them
+    as `**kwargs`, so that a new kwarg of the input goes here and in `_input` only.
+
+    `version` is the row encoding of a Struct column, as in `hash_rows`. `prefix` and
+    `suffix` are the bytes to hash before and after each value.
+    """
+
+    version: int | None
+    null_as: NullAs
+    input_encoding: InputEncoding
+    on_decode_error: OnDecodeError
+    prefix: Affix | None
+    suffix: Affix | None
+
+
+# The kwargs that can hold an expression. `_plugin` sends the expression as an input.
+_AFFIXES = ("prefix", "suffix")
+
 
 def _plugin(
     function_name: str,
@@ -29,7 +79,16 @@ def _plugin(
 
     Every expression in this module is elementwise and lives in the same
     directory, so the call site only ever varies by name, arguments and kwargs.
+
+    A kwarg travels as a pickle, and an expression does not. Therefore a prefix or a
+    suffix that is an expression goes to the end of the inputs, and its kwarg holds
+    the index of that input.
     """
+    for name in _AFFIXES:
+        affix = kwargs.get(name)
+        if isinstance(affix, pl.Expr):
+            args = [*(args if isinstance(args, list) else [args]), affix]
+            kwargs[name] = {"column": len(args) - 1}
     return register_plugin_function(
         plugin_path=_PLUGIN_PATH,
         function_name=function_name,
@@ -50,105 +109,421 @@ def _encode_u64_seed(seed: int) -> int:
     return seed - 2**64 if seed >= 2**63 else seed
 
 
+def _affix(affix: Affix | None) -> Any:
+    if isinstance(affix, str):
+        affix = affix.encode()
+    if isinstance(affix, bytes):
+        return {"bytes": list(affix)}
+    if affix is None or isinstance(affix, pl.Expr):
+        return affix
+    msg = f"a prefix or a suffix must be bytes, str or an expression, got {affix!r}"
+    raise TypeError(msg)
+
+
+def _input(
+    *,
+    version: int | None = None,
+    null_as: NullAs = "propagate",
+    input_encoding: InputEncoding = "utf8",
+    on_decode_error: OnDecodeError = "raise",
+    prefix: Affix | None = None,
+    suffix: Affix | None = None,
+) -> dict[str, Any]:
+    """The kwargs of the input column, which every hasher takes as `InputKwargs`.
+    The defaults are here, and a hasher passes on only the kwargs that its caller gave.
+    Therefore an unknown kwarg raises a TypeError here.
+
+    A pickle has no enum. Therefore a sentinel crosses as a dict with one key, which is
+    the form that the plugin reads for a variant with data.
+    """
+    if input_encoding not in _INPUT_ENCODINGS:
+        names = ", ".join(repr(name) for name in _INPUT_ENCODINGS)
+        msg = f"`input_encoding` must be one of {names}, got {input_encoding!r}"
+        raise ValueError(msg)
+    if on_decode_error not in ("raise", "null"):
+        msg = f"`on_decode_error` must be 'raise' or 'null', got {on_decode_error!r}"
+        raise ValueError(msg)
+    kwargs = {
+        "version": version,
+        "input_encoding": input_encoding,
+        "on_decode_error": on_decode_error,
+        "prefix": _affix(prefix),
+        "suffix": _affix(suffix),
+    }
+    if isinstance(null_as, bytes):
+        return {**kwargs, "null_as": {"sentinel": list(null_as)}}
+    if null_as not in ("propagate", "empty"):
+        msg = f"`null_as` must be 'propagate', 'empty' or bytes, got {null_as!r}"
+        raise ValueError(msg)
+    return {**kwargs, "null_as": null_as}
+
+
+def _seeded_plugin(
+    function_name: str,
+    expr: pl.Expr,
+    seed: int | pl.Expr | None,
+    *,
+    wide: bool,
+    **kwargs: Any,
+) -> pl.Expr:
+    """Call a seeded hasher with one seed, or with a seed for each row.
+
+    An `int` is the fast case and travels as a kwarg, as it always has. An
+    expression travels as a second input instead, and a length-1 result is one
+    seed for every row. The kwarg must still be present, so it is 0 there.
+    `wide` says the seed is a `u64`, which crosses as an `i64`.
+
+    A prefix or a suffix column is an input after the seed. The plugin reads the
+    second input as the seed, so an `int` seed then travels as a literal, which is
+    one seed for every row.
+    """
+    if isinstance(seed, int) and any(
+        isinstance(kwargs.get(name), pl.Expr) for name in _AFFIXES
+    ):
+        seed = pl.lit(seed, dtype=pl.UInt64 if wide else pl.UInt32)
+    if isinstance(seed, pl.Expr):
+        return _plugin(function_name, [expr, seed], seed=0, **kwargs)
+    if wide and seed is not None:
+        seed = _encode_u64_seed(seed)
+    return _plugin(function_name, expr, seed=seed, **kwargs)
+
+
 @pl.api.register_expr_namespace("chash")
 class CryptographicHashingNameSpace:
+    """The cryptographic hashers.
+
+    Each one accepts Utf8 and Binary, and also integers, floats, Boolean, temporal,
+    Categorical and Enum columns, and List, Array and Struct columns of these types.
+    The docs give the bytes of each type under "Input types".
+    """
+
     def __init__(self, expr: pl.Expr):
         self._expr = expr
 
-    def sha2_256(self) -> pl.Expr:
+    def sha2_256(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha256 from SHA-2 family."""
-        return _plugin("sha2_256", self._expr)
+        return _plugin(
+            "sha2_256",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha2_512(self) -> pl.Expr:
+    def sha2_512(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha512 from SHA-2 family."""
-        return _plugin("sha2_512", self._expr)
+        return _plugin(
+            "sha2_512",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha2_384(self) -> pl.Expr:
+    def sha2_384(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha384 from SHA-2 family."""
-        return _plugin("sha2_384", self._expr)
+        return _plugin(
+            "sha2_384",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha2_224(self) -> pl.Expr:
+    def sha2_224(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha224 from SHA-2 family."""
-        return _plugin("sha2_224", self._expr)
+        return _plugin(
+            "sha2_224",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha3_256(self) -> pl.Expr:
+    def sha3_256(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha256 from SHA-3 family."""
-        return _plugin("sha3_256", self._expr)
+        return _plugin(
+            "sha3_256",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha3_512(self) -> pl.Expr:
+    def sha3_512(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha512 from SHA-3 family."""
-        return _plugin("sha3_512", self._expr)
+        return _plugin(
+            "sha3_512",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha3_384(self) -> pl.Expr:
+    def sha3_384(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha384 from SHA-3 family."""
-        return _plugin("sha3_384", self._expr)
+        return _plugin(
+            "sha3_384",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha3_224(self) -> pl.Expr:
+    def sha3_224(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha224 from SHA-3 family."""
-        return _plugin("sha3_224", self._expr)
+        return _plugin(
+            "sha3_224",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def sha3_shake128(self, *, length: int) -> pl.Expr:
+    def sha3_shake128(
+        self,
+        *,
+        length: int,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with shake128 from SHA-3 family."""
-        return _plugin("sha3_shake128", self._expr, length=length)
+        return _plugin(
+            "sha3_shake128",
+            self._expr,
+            length=length,
+            **_input(**kwargs),
+        )
 
-    def blake3(self) -> pl.Expr:
+    def blake3(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with blake3."""
-        return _plugin("blake3", self._expr)
+        return _plugin(
+            "blake3",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def hmac_sha256(self, *, key: str) -> pl.Expr:
+    def hmac_sha256(
+        self,
+        *,
+        key: str,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns hex-encoded HMAC-SHA256 string."""
-        return _plugin("hmac_sha256", self._expr, key=key)
+        return _plugin(
+            "hmac_sha256",
+            self._expr,
+            key=key,
+            **_input(**kwargs),
+        )
 
 
 @pl.api.register_expr_namespace("nchash")
 class NonCryptographicHashingNameSpace:
+    """The non-cryptographic hashers.
+
+    Each one accepts Utf8 and Binary, and also integers, floats, Boolean, temporal,
+    Categorical and Enum columns, and List, Array and Struct columns of these types.
+    The docs give the bytes of each type under "Input types".
+    """
+
     def __init__(self, expr: pl.Expr):
         self._expr = expr
 
-    def wyhash(self) -> pl.Expr:
+    def wyhash(
+        self,
+        *,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint64 hash with wyhash."""
-        return _plugin("wyhash", self._expr)
+        return _plugin(
+            "wyhash",
+            self._expr,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def sha1(self) -> pl.Expr:
+    def sha1(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with sha1."""
-        return _plugin("sha1", self._expr)
+        return _plugin(
+            "sha1",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def md5(self) -> pl.Expr:
+    def md5(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns utf8 hash with md5."""
-        return _plugin("md5", self._expr)
+        return _plugin(
+            "md5",
+            self._expr,
+            **_input(**kwargs),
+        )
 
-    def murmur32(self, *, seed: int = 0) -> pl.Expr:
-        """Takes Utf8 or Binary as input and returns uint32 hash with murmur32."""
-        return _plugin("murmur32", self._expr, seed=seed)
+    def murmur32(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes Utf8 or Binary as input and returns uint32 hash with murmur32.
+
+        `seed` may be an integer expression, for a seed on each row.
+        """
+        return _seeded_plugin(
+            "murmur32",
+            self._expr,
+            seed,
+            wide=False,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def murmur128(self, *, seed: int = 0, return_binary: bool = False) -> pl.Expr:
+    def murmur128(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        return_binary: bool = False,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint128 hash with murmur128.
 
         Set `return_binary` to get the hash as 16 Binary bytes, least significant
         byte first. Use it where the target of a write has no 128-bit integer. The
         bytes and the integer hold the same hash. Those bytes are also the digest
         MurmurHash3 writes, which is what `mmh3.hash_bytes` gives.
+
+        `seed` may be an integer expression, for a seed on each row.
         """
-        return _plugin("murmur128", self._expr, seed=seed, return_binary=return_binary)
+        return _seeded_plugin(
+            "murmur128",
+            self._expr,
+            seed,
+            wide=False,
+            return_binary=return_binary,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def xxhash32(self, *, seed: int = 0) -> pl.Expr:
-        """Takes Utf8 or Binary as input and returns uint32 hash with xxhash32."""
-        return _plugin("xxhash32", self._expr, seed=seed)
+    def kafka_murmur2(
+        self,
+        *,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes Utf8 or Binary as input and returns uint32 hash with Kafka's murmur2.
 
-    def xxhash64(self, *, seed: int = 0) -> pl.Expr:
-        """Takes Utf8 or Binary as input and returns uint64 hash with xxhash64."""
-        return _plugin("xxhash64", self._expr, seed=_encode_u64_seed(seed))
+        This is ``Utils.murmur2`` of the Kafka clients. Set `signed` to get the Java
+        ``int`` that Kafka gives.
+        """
+        return _plugin(
+            "kafka_murmur2",
+            self._expr,
+            signed=signed,
+            **_input(**kwargs),
+        )
+
+    def kafka_partition(
+        self,
+        partitions: int,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes a Utf8 or Binary key as input and returns the int32 partition of Kafka.
+
+        This is the partition that the default partitioner of Kafka gives to a record
+        with this key, in a topic of `partitions` partitions. A null key gives null,
+        because Kafka does not hash it. Therefore `null_as` must be "propagate".
+        """
+        null_as = kwargs.get("null_as", "propagate")
+        if null_as != "propagate":
+            msg = (
+                "Kafka does not hash a null key, so `null_as` must be 'propagate', "
+                f"got {null_as!r}"
+            )
+            raise ValueError(msg)
+        return _plugin(
+            "kafka_partition",
+            self._expr,
+            partitions=partitions,
+            **_input(**kwargs),
+        )
+
+    def redis_slot(self, **kwargs: Unpack[InputKwargs]) -> pl.Expr:
+        """Takes a Utf8 or Binary key as input and returns its uint16 Redis slot.
+
+        This is ``CLUSTER KEYSLOT``: the CRC16 of the key modulo 16384. If the key has
+        a hash tag, such as ``{user1000}`` in ``{user1000}.following``, only the hash
+        tag is hashed.
+        """
+        return _plugin(
+            "redis_slot",
+            self._expr,
+            **_input(**kwargs),
+        )
+
+    def cassandra_token(self) -> pl.Expr:
+        """Takes a partition key as input and returns the int64 token of Cassandra.
+
+        This is the token of ``Murmur3Partitioner``. The key has the bytes that
+        Cassandra serializes: a String is ``text``, Binary is ``blob``, Int32 is
+        ``int`` and Int64 is ``bigint``, in big-endian order. A key of more than one
+        column is a Struct of the columns, in the order of the primary key.
+        """
+        return _plugin("cassandra_token", self._expr)
+
+    def xxhash32(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes Utf8 or Binary as input and returns uint32 hash with xxhash32.
 
-    def xxh3_64(self, *, seed: int = 0) -> pl.Expr:
-        """Takes Utf8 or Binary as input and returns uint64 hash with XXH3 64bit."""
-        return _plugin("xxh3_64", self._expr, seed=_encode_u64_seed(seed))
+        `seed` may be an integer expression, for a seed on each row.
+        """
+        return _seeded_plugin(
+            "xxhash32",
+            self._expr,
+            seed,
+            wide=False,
+            signed=signed,
+            **_input(**kwargs),
+        )
+
+    def xxhash64(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes Utf8 or Binary as input and returns uint64 hash with xxhash64.
+
+        `seed` may be an integer expression, for a seed on each row.
+        """
+        return _seeded_plugin(
+            "xxhash64",
+            self._expr,
+            seed,
+            wide=True,
+            signed=signed,
+            **_input(**kwargs),
+        )
+
+    def xxh3_64(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes Utf8 or Binary as input and returns uint64 hash with XXH3 64bit.
+
+        `seed` may be an integer expression, for a seed on each row.
+        """
+        return _seeded_plugin(
+            "xxh3_64",
+            self._expr,
+            seed,
+            wide=True,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
     def xxh3_128(
         self,
         *,
-        seed: int = 0,
+        seed: int | pl.Expr = 0,
         return_binary: bool = False,
         byte_order: Literal["little", "big"] | None = None,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
     ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint128 hash with XXH3 128bit.
 
@@ -159,6 +534,8 @@ class NonCryptographicHashingNameSpace:
         writes, which is what `xxhash.xxh128_digest` gives. "little" is the bytes of
         the integer, which is what this expression wrote before 0.8.0. The default is
         "little" and it warns, because the compatible order is not the correct one.
+
+        `seed` may be an integer expression, for a seed on each row.
         """
         if byte_order not in (None, "little", "big"):
             msg = f"`byte_order` must be 'little' or 'big', got {byte_order!r}"
@@ -170,70 +547,199 @@ class NonCryptographicHashingNameSpace:
                 UserWarning,
                 stacklevel=2,
             )
-        return _plugin(
+        return _seeded_plugin(
             "xxh3_128",
             self._expr,
-            seed=_encode_u64_seed(seed),
+            seed,
+            wide=True,
             return_binary=return_binary,
             big_endian=byte_order == "big",
+            signed=signed,
+            **_input(**kwargs),
         )
 
-    def farmhash32(self) -> pl.Expr:
+    def farmhash32(
+        self,
+        *,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint32 hash with FarmHash fingerprint32."""
-        return _plugin("farmhash32", self._expr)
+        return _plugin(
+            "farmhash32",
+            self._expr,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def farmhash64(self) -> pl.Expr:
+    def farmhash64(
+        self,
+        *,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint64 hash with FarmHash fingerprint64."""
-        return _plugin("farmhash64", self._expr)
+        return _plugin(
+            "farmhash64",
+            self._expr,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def cityhash32(self) -> pl.Expr:
+    def cityhash32(
+        self,
+        *,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint32 hash with CityHash32."""
-        return _plugin("cityhash32", self._expr)
+        return _plugin(
+            "cityhash32",
+            self._expr,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def cityhash64(self, *, seed: int | None = None) -> pl.Expr:
+    def cityhash64(
+        self,
+        *,
+        seed: int | pl.Expr | None = None,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint64 hash with CityHash64.
 
         Without a seed this is `CityHash64`, with one `CityHash64WithSeed` — a
-        different value even for `seed=0`.
+        different value even for `seed=0`. `seed` may be an integer expression, for
+        a seed on each row.
         """
         if seed is None:
-            return _plugin("cityhash64", self._expr)
+            return _plugin(
+                "cityhash64",
+                self._expr,
+                signed=signed,
+                **_input(**kwargs),
+            )
 
-        return _plugin("cityhash64_with_seed", self._expr, seed=_encode_u64_seed(seed))
+        return _seeded_plugin(
+            "cityhash64_with_seed",
+            self._expr,
+            seed,
+            wide=True,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def cityhash128(self, *, return_binary: bool = False) -> pl.Expr:
+    def cityhash128(
+        self,
+        *,
+        return_binary: bool = False,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint128 hash with CityHash128.
 
         Set `return_binary` to get the hash as 16 Binary bytes, least significant
         byte first. Use it where the target of a write has no 128-bit integer. The
         bytes and the integer hold the same hash.
         """
-        return _plugin("cityhash128", self._expr, return_binary=return_binary)
+        return _plugin(
+            "cityhash128",
+            self._expr,
+            return_binary=return_binary,
+            signed=signed,
+            **_input(**kwargs),
+        )
+
+    def gxhash32(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes Utf8 or Binary as input and returns uint32 hash with GxHash.
+
+        `seed` may be an integer expression, for a seed on each row.
+        """
+        return _seeded_plugin(
+            "gxhash32",
+            self._expr,
+            seed,
+            wide=True,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def gxhash32(self, *, seed: int = 0) -> pl.Expr:
-        """Takes Utf8 or Binary as input and returns uint32 hash with GxHash."""
-        return _plugin("gxhash32", self._expr, seed=_encode_u64_seed(seed))
+    def gxhash64(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
+        """Takes Utf8 or Binary as input and returns uint64 hash with GxHash.
 
-    def gxhash64(self, *, seed: int = 0) -> pl.Expr:
-        """Takes Utf8 or Binary as input and returns uint64 hash with GxHash."""
-        return _plugin("gxhash64", self._expr, seed=_encode_u64_seed(seed))
+        `seed` may be an integer expression, for a seed on each row.
+        """
+        return _seeded_plugin(
+            "gxhash64",
+            self._expr,
+            seed,
+            wide=True,
+            signed=signed,
+            **_input(**kwargs),
+        )
 
-    def gxhash128(self, *, seed: int = 0, return_binary: bool = False) -> pl.Expr:
+    def gxhash128(
+        self,
+        *,
+        seed: int | pl.Expr = 0,
+        return_binary: bool = False,
+        signed: bool = False,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Takes Utf8 or Binary as input and returns uint128 hash with GxHash.
 
         Set `return_binary` to get the hash as 16 Binary bytes, least significant
         byte first. Use it where the target of a write has no 128-bit integer. The
         bytes and the integer hold the same hash.
+
+        `seed` may be an integer expression, for a seed on each row.
         """
-        return _plugin(
+        return _seeded_plugin(
             "gxhash128",
             self._expr,
-            seed=_encode_u64_seed(seed),
+            seed,
+            wide=True,
             return_binary=return_binary,
+            signed=signed,
+            **_input(**kwargs),
         )
 
 
-def _length_expr(length: int | str | pl.Expr) -> pl.Expr:
+def _length_plugin(
+    function_name: str,
+    expr: pl.Expr,
+    length: int | str | pl.Expr,
+    *,
+    name: str,
+    **kwargs: Any,
+) -> pl.Expr:
+    """Call an encoder with one length, or with a length for each row.
+
+    An `int` travels as the kwarg `name`, as a seed does. The output type function
+    then checks its range when polars builds the plan. Any other length travels as a
+    second input, and the kwarg is `None`.
+    """
+    if isinstance(length, int):
+        return _plugin(function_name, expr, **{name: length}, **kwargs)
+    return _plugin(
+        function_name, [expr, _length_expr(length)], **{name: None}, **kwargs
+    )
+
+
+def _length_expr(length: float | str | pl.Expr) -> pl.Expr:
     if isinstance(length, str):
         expr = pl.col(length)
     elif isinstance(length, pl.Expr):
@@ -248,17 +754,33 @@ class GeoHashingNameSpace:
     def __init__(self, expr: pl.Expr):
         self._expr = expr
 
-    def to_coords(self) -> pl.Expr:
-        """Takes Utf8 as input and returns a struct of the coordinates."""
-        return _plugin("ghash_decode", self._expr)
+    def to_coords(self, *, strict: bool = True) -> pl.Expr:
+        """Takes Utf8 as input and returns a struct of the coordinates.
+
+        With ``strict=False`` an invalid geohash yields null coordinates instead of
+        raising.
+        """
+        return _plugin("ghash_decode", self._expr, strict=strict)
 
-    def from_coords(self, len: int | str | pl.Expr = 12) -> pl.Expr:
-        """Takes Struct with latitude, longitude as input and returns utf8 hash using geohash."""
-        return _plugin("ghash_encode", [self._expr, _length_expr(len)])
+    def from_coords(
+        self, len: int | str | pl.Expr = 12, *, strict: bool = True
+    ) -> pl.Expr:
+        """Takes Struct with latitude, longitude as input and returns utf8 hash using geohash.
 
-    def neighbors(self) -> pl.Expr:
-        """Takes Utf8 hash as input and returns a struct of the neighbors."""
-        return _plugin("ghash_neighbors", self._expr)
+        With ``strict=False`` a coordinate out of range yields null instead of
+        raising. Length stays strict either way.
+        """
+        return _length_plugin(
+            "ghash_encode", self._expr, len, name="length", strict=strict
+        )
+
+    def neighbors(self, *, strict: bool = True) -> pl.Expr:
+        """Takes Utf8 hash as input and returns a struct of the neighbors.
+
+        With ``strict=False`` an invalid geohash yields null neighbors instead of
+        raising.
+        """
+        return _plugin("ghash_neighbors", self._expr, strict=strict)
 
 
 @pl.api.register_expr_namespace("h3")
@@ -266,9 +788,15 @@ class H3NameSpace:
     def __init__(self, expr: pl.Expr):
         self._expr = expr
 
-    def from_coords(self, len: int = 12) -> pl.Expr:
-        """Takes Struct with latitude, longitude as input and returns utf8 H3 spatial index."""
-        return _plugin("h3_encode", [self._expr, _length_expr(len)])
+    def from_coords(self, len: int = 12, *, strict: bool = True) -> pl.Expr:
+        """Takes Struct with latitude, longitude as input and returns utf8 H3 spatial index.
+
+        With ``strict=False`` an invalid coordinate yields null instead of raising.
+        Resolution stays strict either way.
+        """
+        return _length_plugin(
+            "h3_encode", self._expr, len, name="length", strict=strict
+        )
 
 
 @pl.api.register_expr_namespace("timehash")
@@ -294,21 +822,27 @@ class TimeHashingNameSpace:
         raising. A ``when``/``then`` guard cannot do this, because polars evaluates
         both branches over the whole column. Precision stays strict either way.
         """
-        return _plugin(
-            "thash_encode", [self._expr, _length_expr(precision)], strict=strict
+        return _length_plugin(
+            "thash_encode", self._expr, precision, name="precision", strict=strict
         )
 
-    def to_datetime(self) -> pl.Expr:
+    def to_datetime(self, *, strict: bool = True) -> pl.Expr:
         """Takes Utf8 hash as input and returns the midpoint of its window as Datetime.
 
         The hash holds an instant, not a wall clock, so the zone is not recoverable.
         The result is UTC; use ``.dt.convert_time_zone(tz)`` for another zone.
+
+        With ``strict=False`` an invalid timehash yields null instead of raising.
         """
-        return _plugin("thash_decode", self._expr)
+        return _plugin("thash_decode", self._expr, strict=strict)
+
+    def neighbors(self, *, strict: bool = True) -> pl.Expr:
+        """Takes Utf8 hash as input and returns a struct of the preceding and succeeding hash.
 
-    def neighbors(self) -> pl.Expr:
-        """Takes Utf8 hash as input and returns a struct of the preceding and succeeding hash."""
-        return _plugin("thash_neighbors", self._expr)
+        With ``strict=False`` an invalid timehash yields null neighbors instead of
+        raising.
+        """
+        return _plugin("thash_neighbors", self._expr, strict=strict)
 
 
 class UUIDNamespace(str, Enum):
@@ -325,16 +859,30 @@ class UUIDHashNameSpace:
     def __init__(self, expr: pl.Expr):
         self._expr = expr
 
-    def uuid5(self, namespace: UUIDNamespace | str = UUIDNamespace.DNS) -> pl.Expr:
+    def uuid5(
+        self,
+        namespace: UUIDNamespace | str = UUIDNamespace.DNS,
+        *,
+        strict: bool = True,
+        **kwargs: Unpack[InputKwargs],
+    ) -> pl.Expr:
         """Generate UUID5 from Utf8 or Binary input using specified namespace.
 
         Args: namespace:
         UUIDNamespace.{DNS | URL | OID | X500} or a custom UUID string.
 
+        With ``strict=False`` an invalid namespace yields a null for every row
+        instead of raising.
+
         Returns:
             Expression producing UUID5 strings.
         """
-        return _plugin("uuid5", [self._expr, pl.lit(namespace)])
+        return _plugin(
+            "uuid5",
+            [self._expr, pl.lit(namespace)],
+            strict=strict,
+            **_input(**kwargs),
+        )
 
     def uuid5_concat(self, other: pl.Expr, default: str | None = None) -> pl.Expr:
         """Concatenate two columns and generate UUID5 using DNS namespace.
@@ -352,6 +900,143 @@ class UUIDHashNameSpace:
         return _plugin("uuid5_concat", [self._expr, other])
 
 
+@pl.api.register_expr_namespace("clickhouse")
+class ClickHouseHashingNameSpace:
+    """The hash functions of ClickHouse, with the same results as ClickHouse.
+
+    Each method takes the input as the arguments of the ClickHouse function. A Struct
+    is more than one argument, one for each field, as in ``cityHash64(a, b)``. A List
+    is an ``Array``. The type of each column must be the ClickHouse type of the
+    argument, because ``Int32(1)`` and ``Int64(1)`` have different hashes.
+    """
+
+    def __init__(self, expr: pl.Expr):
+        self._expr = expr
+
+    def city_hash64(self) -> pl.Expr:
+        """Takes Utf8, Binary, integers or a Struct of them and returns ``cityHash64``.
+
+        ClickHouse uses CityHash v1.0.2, which is not the v1.1.1 of
+        ``nchash.cityhash64``.
+        """
+        return _plugin("clickhouse_city_hash64", self._expr)
+
+    def sip_hash64(self) -> pl.Expr:
+        """Takes Utf8, Binary, integers or a Struct of them and returns
+        ``sipHash64``."""
+        return _plugin("clickhouse_sip_hash64", self._expr)
+
+    def murmur_hash3_64(self) -> pl.Expr:
+        """Takes Utf8, Binary, integers or a Struct of them and returns
+        ``murmurHash3_64``."""
+        return _plugin("clickhouse_murmur_hash3_64", self._expr)
+
+    def half_md5(self) -> pl.Expr:
+        """Takes Utf8, Binary, integers or a Struct of them and returns
+        ``halfMD5``."""
+        return _plugin("clickhouse_half_md5", self._expr)
+
+
+@pl.api.register_expr_namespace("jvm")
+class JVMHashingNameSpace:
+    """The hashes of Java and of Guava, as the Java ``int`` or ``long`` they give.
+
+    A Java value keeps its type, and ``Integer`` and ``Long`` can hash the same value
+    to two hashes. Therefore the type of each column must be the Java type: Int8 is
+    ``byte``, Int16 is ``short``, Int32 is ``int`` and Int64 is ``long``. Java has no
+    unsigned integer, and an unsigned column is an error.
+    """
+
+    def __init__(self, expr: pl.Expr):
+        self._expr = expr
+
+    def hash_code(self) -> pl.Expr:
+        """Takes a String, Binary, number or Boolean and returns the int32 ``hashCode``.
+
+        A String is ``String.hashCode``, Binary is ``Arrays.hashCode(byte[])``, and a
+        number or a Boolean is the ``hashCode`` of its boxed Java type. A Struct gives
+        ``Objects.hash`` of its fields, in order.
+        """
+        return _plugin("jvm_hash_code", self._expr)
+
+    def guava_murmur3_32(
+        self,
+        *,
+        seed: int = 0,
+        input_encoding: InputEncoding = "utf8",
+        on_decode_error: OnDecodeError = "raise",
+    ) -> pl.Expr:
+        """Returns the int32 ``Hashing.murmur3_32_fixed(seed)`` of Guava.
+
+        This is ``asInt()`` of the hash. The deprecated ``murmur3_32`` gives the same
+        hash, except for a string with a character above U+FFFF in UTF-8.
+
+        A String is ``hashString(value, charset)``, with the charset of
+        ``input_encoding``. ``"utf16le"`` is ``hashUnencodedChars``. A number is the
+        ``put`` of its Java type, and a Struct is a ``Funnel`` that puts each field in
+        order.
+        """
+        return _plugin(
+            "guava_murmur3_32",
+            self._expr,
+            seed=seed,
+            input_encoding=input_encoding,
+            on_decode_error=on_decode_error,
+        )
+
+    def guava_murmur3_128(
+        self,
+        *,
+        seed: int = 0,
+        input_encoding: InputEncoding = "utf8",
+        on_decode_error: OnDecodeError = "raise",
+    ) -> pl.Expr:
+        """Returns the int64 ``Hashing.murmur3_128(seed)`` of Guava.
+
+        This is ``asLong()`` of the hash, the first 8 bytes of the digest. The input is
+        read as in ``guava_murmur3_32``.
+        """
+        return _plugin(
+            "guava_murmur3_128",
+            self._expr,
+            seed=seed,
+            input_encoding=input_encoding,
+            on_decode_error=on_decode_error,
+        )
+
+
+@pl.api.register_expr_namespace("iceberg")
+class IcebergNameSpace:
+    """The ``bucket[N]`` and ``truncate[W]`` partition transforms of Apache Iceberg.
+
+    Iceberg reads each value as its own type. Therefore the type of the column must
+    be the Iceberg type: Int8, Int16 and Int32 are ``int``, Int64 is ``long``, Date is
+    ``date``, Time is ``time``, Datetime is ``timestamp``, String is ``string``, and
+    Binary is ``binary``, ``fixed`` or ``uuid``.
+    """
+
+    def __init__(self, expr: pl.Expr):
+        self._expr = expr
+
+    def bucket(self, buckets: int) -> pl.Expr:
+        """Returns the int32 ``bucket[buckets]`` of each value.
+
+        This is the 32-bit MurmurHash3 of the value without its sign bit, modulo
+        ``buckets``. An ``int`` hashes as a ``long``, and a Datetime of any unit is
+        hashed in microseconds. A null value gives null.
+        """
+        return _plugin("iceberg_bucket", self._expr, buckets=buckets)
+
+    def truncate(self, width: int) -> pl.Expr:
+        """Returns the ``truncate[width]`` of each value.
+
+        An integer or a Decimal is rounded down to a multiple of ``width``. A Decimal
+        rounds its unscaled value, and keeps its scale. A String keeps its first
+        ``width`` code points, and Binary keeps its first ``width`` bytes.
+        """
+        return _plugin("iceberg_truncate", self._expr, width=width)
+
+
 class HExpr(pl.Expr):
     @property
     def chash(self) -> CryptographicHashingNameSpace:
@@ -377,6 +1062,18 @@ class HExpr(pl.Expr):
     def uuidhash(self) -> UUIDHashNameSpace:
         return UUIDHashNameSpace(self)
 
+    @property
+    def clickhouse(self) -> ClickHouseHashingNameSpace:
+        return ClickHouseHashingNameSpace(self)
+
+    @property
+    def jvm(self) -> JVMHashingNameSpace:
+        return JVMHashingNameSpace(self)
+
+    @property
+    def iceberg(self) -> IcebergNameSpace:
+        return IcebergNameSpace(self)
+
 
 class HashColumn(Protocol):
     def __call__(
@@ -402,6 +1099,15 @@ class HashColumn(Protocol):
     @property
     def uuidhash(self) -> UUIDHashNameSpace: ...
 
+    @property
+    def clickhouse(self) -> ClickHouseHashingNameSpace: ...
+
+    @property
+    def jvm(self) -> JVMHashingNameSpace: ...
+
+    @property
+    def iceberg(self) -> IcebergNameSpace: ...
+
 
 class HashConcatStr(Protocol):
     def __call__(
@@ -505,4 +1211,253 @@ def hash_rows(
     )
 
 
-__all__ = ["UUIDNamespace", "__version__", "col", "concat_str", "hash_rows"]
+def postgres_partition(
+    exprs: IntoExpr | Iterable[IntoExpr],
+    *more_exprs: IntoExpr,
+    modulus: int,
+) -> HExpr:
+    """Gives the hash partition that PostgreSQL gives to each row.
+
+    A table that is ``PARTITION BY HASH (a, b)`` puts a row in the partition ``FOR
+    VALUES WITH (MODULUS m, REMAINDER r)``. This function gives ``r``::
+
+        df.group_by(plh.postgres_partition("a", "b", modulus=8))
+
+    Each column is hashed as the PostgreSQL type of its values: String is ``text``,
+    Binary is ``bytea``, an integer is ``int2``, ``int4`` or ``int8``, a float is
+    ``float4`` or ``float8``, and Date, Time and Datetime are ``date``, ``time`` and
+    ``timestamp`` or ``timestamptz``. PostgreSQL skips a null value. Therefore a row
+    with a null also has a partition.
+
+    Args:
+        exprs: The columns of the partition key, in the order of the key.
+        *more_exprs: More columns, as positional arguments.
+        modulus: The modulus of the partitions, from 1 to 2147483647.
+
+    Returns:
+        An expression that makes Int32, from 0 to ``modulus - 1``. No value is null.
+    """
+    return cast(
+        HExpr,
+        _plugin(
+            "postgres_partition",
+            pl.struct(_row_fields(exprs, more_exprs)),
+            modulus=modulus,
+        ),
+    )
+
+
+def hive_bucket(
+    exprs: IntoExpr | Iterable[IntoExpr],
+    *more_exprs: IntoExpr,
+    buckets: int,
+) -> HExpr:
+    """Gives the bucket that Hive gives to each row.
+
+    A table that is ``CLUSTERED BY (a, b) INTO n BUCKETS`` puts a row in the file of
+    its bucket. This function gives that bucket::
+
+        df.group_by(plh.hive_bucket("a", "b", buckets=32))
+
+    This is the bucket of a table of ``bucketing_version`` 1, which Hive 1 and Hive 2
+    write. Each column is hashed as its Hive type: String is ``string``, Binary is
+    ``binary``, Int8 is ``tinyint``, Int16 is ``smallint``, Int32 is ``int``, Int64
+    is ``bigint``, and a float is ``float`` or ``double``. A null value hashes as 0.
+
+    Args:
+        exprs: The bucket columns, in the order of ``CLUSTERED BY``.
+        *more_exprs: More columns, as positional arguments.
+        buckets: The number of buckets, from 1 to 2147483647.
+
+    Returns:
+        An expression that makes Int32, from 0 to ``buckets - 1``. No value is null.
+    """
+    return cast(
+        HExpr,
+        _plugin(
+            "hive_bucket",
+            pl.struct(_row_fields(exprs, more_exprs)),
+            buckets=buckets,
+        ),
+    )
+
+
+def spark_hash(
+    exprs: IntoExpr | Iterable[IntoExpr],
+    *more_exprs: IntoExpr,
+) -> HExpr:
+    """Gives the ``hash`` of Spark SQL for the columns of each row.
+
+    This is ``hash(a, b)`` in Spark, which is Murmur3 with the seed 42. The hash of
+    each column is the seed of the next column, and Spark skips a null. Use it to
+    compare a table with a Spark table, or to find the bucket of a row::
+
+        df.select(plh.spark_hash("a", "b"))
+
+    Each column is hashed as its Spark type: Int32 is ``int``, Int64 is ``bigint``,
+    String is ``string``, Decimal is ``decimal`` of the same precision, Date is
+    ``date`` and Datetime is ``timestamp``. A List is an ``array``, and a Struct is a
+    ``struct``.
+
+    Args:
+        exprs: The columns, in the order of the arguments of ``hash``.
+        *more_exprs: More columns, as positional arguments.
+
+    Returns:
+        An expression that makes Int32. No value is null, and a row of nulls gives 42.
+    """
+    return cast(HExpr, _plugin("spark_hash", pl.struct(_row_fields(exprs, more_exprs))))
+
+
+def spark_xxhash64(
+    exprs: IntoExpr | Iterable[IntoExpr],
+    *more_exprs: IntoExpr,
+) -> HExpr:
+    """Gives the ``xxhash64`` of Spark SQL for the columns of each row.
+
+    This is XXH64 with the seed 42, and it reads the columns as ``spark_hash`` does.
+
+    Args:
+        exprs: The columns, in the order of the arguments of ``xxhash64``.
+        *more_exprs: More columns, as positional arguments.
+
+    Returns:
+        An expression that makes Int64. No value is null, and a row of nulls gives 42.
+    """
+    return cast(
+        HExpr, _plugin("spark_xxhash64", pl.struct(_row_fields(exprs, more_exprs)))
+    )
+
+
+def hash(
+    expr: IntoExpr,
+    algorithm: str,
+    *,
+    seed: int | None = None,
+    key: str | None = None,
+    length: int | None = None,
+    return_binary: bool = False,
+    byte_order: Literal["little", "big"] | None = None,
+    signed: bool = False,
+    **kwargs: Unpack[InputKwargs],
+) -> HExpr:
+    """Hashes with the algorithm that `algorithm` names.
+
+    Use this where the name of the algorithm comes from a configuration, and not
+    from the code::
+
+        df.select(plh.hash("foo", config["algorithm"]))
+
+    Each name is the name of a method in `chash` or `nchash`, and it gives the same
+    output as that method. `cityhash64_with_seed` is the name for `cityhash64` with a
+    seed. An unknown name raises an error that lists all the names.
+
+    Args:
+        expr: The column to hash.
+        algorithm: The name of the algorithm, for example "sha2_256" or "xxh3_64".
+        seed: The seed of a seeded algorithm. The default is the default of the
+            method. An algorithm without a seed rejects it.
+        key: The key of "hmac_sha256", which needs it. The others reject it.
+        length: The number of bytes of "sha3_shake128", which needs it. The others
+            reject it.
+        return_binary: Gives the hash of a 128-bit algorithm as 16 Binary bytes.
+        byte_order: The order of those bytes, "little" or "big". The default is
+            "little", which is what the methods give. Only "xxh3_128" takes "big".
+        signed: Gives the hash of an integer algorithm as the signed integer of the
+            same bits. The others reject it.
+        version: The row encoding of a Struct column. The default is the latest
+            version. See `hash_rows`.
+        null_as: What to do with a null value. See `NullAs`.
+        input_encoding: The bytes of a String value. See `InputEncoding`.
+        on_decode_error: What to do with a value that does not decode, "raise" or
+            "null".
+        prefix: The bytes to hash before each value. See `Affix`.
+        suffix: The bytes to hash after each value. See `Affix`.
+
+    Returns:
+        An expression of the data type of the algorithm. The name of the algorithm
+        and the other arguments are checked when polars makes the plan, before it
+        reads data.
+    """
+    if byte_order not in (None, "little", "big"):
+        msg = f"`byte_order` must be 'little' or 'big', got {byte_order!r}"
+        raise ValueError(msg)
+    return cast(
+        HExpr,
+        _plugin(
+            "hash",
+            expr,
+            algorithm=algorithm,
+            seed=None if seed is None else _encode_u64_seed(seed),
+            key=key,
+            length=length,
+            return_binary=return_binary,
+            big_endian=byte_order == "big",
+            signed=signed,
+            **_input(**kwargs),
+        ),
+    )
+
+
+def multi_digest(
+    expr: IntoExpr,
+    algorithms: str | Iterable[str],
+    **kwargs: Unpack[InputKwargs],
+) -> HExpr:
+    """Hashes with each algorithm that `algorithms` names, and reads each value once.
+
+    Use this for several digests of one column of large values::
+
+        df.select(plh.multi_digest("blob", ["md5", "sha1", "sha2_256", "blake3"]))
+
+    Each value leaves memory once, and each hasher reads it there. A separate
+    expression for each algorithm reads each value again.
+
+    Args:
+        expr: The column to hash.
+        algorithms: The names of the algorithms, as in `hash`. Each one must need no
+            argument. Therefore "hmac_sha256" and "sha3_shake128" are errors, and a
+            seeded algorithm takes its default seed.
+        version: The row encoding of a Struct column. See `hash_rows`.
+        null_as: What to do with a null value. See `NullAs`.
+        input_encoding: The bytes of a String value. See `InputEncoding`.
+        on_decode_error: What to do with a value that does not decode, "raise" or
+            "null".
+        prefix: The bytes to hash before each value. See `Affix`.
+        suffix: The bytes to hash after each value. See `Affix`.
+
+    Returns:
+        A Struct with one field for each algorithm, in the given order. Each field has
+        the name of its algorithm and the data type that `hash` gives. A null value
+        gives a null row, unless `null_as` gives it bytes.
+    """
+    names = [algorithms] if isinstance(algorithms, str) else list(algorithms)
+    return cast(
+        HExpr,
+        _plugin(
+            "multi_digest",
+            expr,
+            algorithms=names,
+            **_input(**kwargs),
+        ),
+    )
+
+
+__all__ = [
+    "Affix",
+    "InputEncoding",
+    "InputKwargs",
+    "NullAs",
+    "OnDecodeError",
+    "UUIDNamespace",
+    "__version__",
+    "col",
+    "concat_str",
+    "hash",
+    "hash_rows",
+    "hive_bucket",
+    "multi_digest",
+    "postgres_partition",
+    "spark_hash",
+    "spark_xxhash64",
+]
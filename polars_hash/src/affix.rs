@@ -0,0 +1,159 @@
+//! The bytes that a hasher reads before and after each value, such as a salt and a
+//! pepper.
+//!
+//! A digest with an `update` call, as SHA, MD5, BLAKE3 and HMAC have, reads the three
+//! parts of an [`Affixed`] value one after the other. Farmhash, cityhash, gxhash,
+//! murmur3 and the other integer hashers hash one slice only, and their digest of the
+//! parts is not their digest of the whole. Therefore [`Affixed::joined`] writes the
+//! parts into one buffer of each thread, and uses it for each row.
+//!
+//! A column of prefixes or suffixes has other bytes on each row. The walks read the
+//! bytes of each row from that column as they hash, as they read a seed column. The
+//! parts never become a column of their own.
+
+use crate::canonical::{canonical_bytes, check_canonical};
+use crate::shared::{at_row, element_rows};
+use polars::prelude::*;
+use serde::Deserialize;
+use std::cell::Cell;
+
+/// The bytes before or after each value.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Affix {
+    /// The same bytes on each row.
+    Bytes(Vec<u8>),
+    /// The input of the expression at this index. [`Affix::resolve`] reads it.
+    Column(usize),
+    /// The bytes of each row, or one row for all of them.
+    #[serde(skip)]
+    Rows(BinaryChunked),
+}
+
+impl Affix {
+    /// Changes the index of an input into the bytes of that input. A column of any
+    /// data type that a hasher reads gives the bytes that the hasher reads.
+    pub fn resolve(self, inputs: &[Series], name: &str) -> PolarsResult<Self> {
+        let Affix::Column(index) = self else {
+            return Ok(self);
+        };
+        let column = inputs
+            .get(index)
+            .ok_or_else(|| polars_err!(ComputeError: "the {} column is missing", name))?;
+        Ok(Affix::Rows(canonical_bytes(column)?.rechunk().into_owned()))
+    }
+
+    /// Gives each element of a List or an Array column `s` the bytes of its row, as
+    /// [`crate::shared::hash_elements`] gives a seed column. A hasher reads the
+    /// elements, and a column of bytes has one row for each row of `s`. Another
+    /// column, or one row for all the rows, stays as it is.
+    pub fn for_elements_of(self, s: &Series, name: &str) -> PolarsResult<Self> {
+        match &self {
+            Affix::Rows(rows) if rows.len() != 1 => {
+                let Some(elements) = element_rows(s)? else {
+                    return Ok(self);
+                };
+                self.check_len(s.len(), name)?;
+                Ok(Affix::Rows(rows.take(elements.as_slice())?))
+            }
+            _ => Ok(self),
+        }
+    }
+
+    /// Checks, when polars builds the plan, that a column of the affix is one of the
+    /// inputs and has bytes that a hasher reads.
+    pub fn check_field(&self, fields: &[Field], name: &str) -> PolarsResult<()> {
+        match self {
+            Affix::Column(index) => match fields.get(*index) {
+                Some(field) => check_canonical(field.dtype()),
+                None => polars_bail!(ComputeError: "the {} column is missing", name),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a column of bytes has one row, or a row for each of `len` values.
+    pub fn check_len(&self, len: usize, name: &str) -> PolarsResult<()> {
+        match self {
+            Affix::Rows(rows) if rows.len() != 1 && rows.len() != len => polars_bail!(
+                ShapeMismatch:
+                "value column has length {} and {} has length {}, expected equal lengths or a scalar",
+                len, name, rows.len()
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Gives the bytes at `row`. A null is an error, as a null seed is: no bytes are a
+    /// default for it.
+    pub fn at(&self, row: usize, name: &str) -> PolarsResult<&[u8]> {
+        match self {
+            Affix::Bytes(bytes) => Ok(bytes),
+            Affix::Rows(rows) => {
+                let row = if rows.len() == 1 { 0 } else { row };
+                rows.get(row).ok_or_else(|| {
+                    at_row(
+                        polars_err!(ComputeError: "{} may not be null", name),
+                        row,
+                        rows.name(),
+                    )
+                })
+            }
+            Affix::Column(_) => polars_bail!(ComputeError: "the {} column was not read", name),
+        }
+    }
+}
+
+/// A value with the bytes that a hasher reads before and after it.
+#[derive(Clone, Copy)]
+pub struct Affixed<'a> {
+    pub prefix: &'a [u8],
+    pub value: &'a [u8],
+    pub suffix: &'a [u8],
+}
+
+thread_local! {
+    static BUFFER: Cell<Vec<u8>> = const { Cell::new(Vec::new()) };
+}
+
+impl<'a> Affixed<'a> {
+    /// A value with no prefix and no suffix.
+    pub fn bare(value: &'a [u8]) -> Self {
+        Affixed {
+            prefix: &[],
+            value,
+            suffix: &[],
+        }
+    }
+
+    /// The prefix, the value and the suffix, in order. A digest gives each one to its
+    /// `update` call.
+    pub fn parts(self) -> [&'a [u8]; 3] {
+        [self.prefix, self.value, self.suffix]
+    }
+
+    /// The number of bytes of the three parts.
+    pub fn len(self) -> usize {
+        self.prefix.len() + self.value.len() + self.suffix.len()
+    }
+
+    /// Runs `op` on the three parts as one slice, for a hasher that reads one slice
+    /// only. A value without a prefix and a suffix goes to `op` as it is.
+    ///
+    /// The buffer leaves its cell while `op` runs. A hasher that waits on the thread
+    /// pool can run a row of another task on this thread, and that row then takes a
+    /// new buffer and not this one.
+    pub fn joined<R>(self, op: impl FnOnce(&[u8]) -> R) -> R {
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            return op(self.value);
+        }
+        let mut buffer = BUFFER.take();
+        buffer.clear();
+        self.parts()
+            .into_iter()
+            .for_each(|part| buffer.extend_from_slice(part));
+        let out = op(&buffer);
+        BUFFER.set(buffer);
+        out
+    }
+}
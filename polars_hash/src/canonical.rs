@@ -0,0 +1,141 @@
+//! Changes a column that is not String or Binary into the bytes that a hasher reads.
+//!
+//! A hasher reads bytes, and a number has more than one byte form. A cast to String
+//! is slow, and its bytes depend on the format of the number. This module gives each
+//! value one form of fixed width, in little-endian order. The documentation gives the
+//! table, and these bytes do not change.
+//!
+//! The rules are the rules of `row_encode`, so that a value has one hash whatever its
+//! storage. An integer of 8 to 64 bits gives the bytes of the 64-bit integer of its
+//! sign, and a 128-bit integer keeps its 16 bytes. A float is an `f64` without `-0.0`
+//! and with one NaN. A `Date` is its `i32` count of days. A `Time` is an `i64` count of
+//! nanoseconds, and a `Datetime` or a `Duration` is an `i128` count of nanoseconds,
+//! whatever its unit. A category is its string.
+
+use crate::row_encode::per_unit;
+use polars::prelude::*;
+
+/// Polars reads `-0.0` and `0.0` as one value. It also reads all the NaN payloads as
+/// one value. Therefore the two equal values must give the same bytes.
+pub fn normalize_float(value: f64) -> f64 {
+    if value.is_nan() {
+        f64::NAN
+    } else {
+        value + 0.0
+    }
+}
+
+/// Writes the bytes of each value of `ca`. The bytes of a value are short, and a
+/// Binary view keeps up to 12 bytes in the view itself. Therefore most of these
+/// columns need no buffer for their bytes.
+pub fn fixed_width<T, const N: usize>(
+    ca: &ChunkedArray<T>,
+    bytes: impl Fn(T::Native) -> [u8; N],
+) -> BinaryChunked
+where
+    T: PolarsNumericType,
+{
+    let mut builder = BinaryChunkedBuilder::new(ca.name().clone(), ca.len());
+    for value in ca.iter() {
+        match value {
+            Some(value) => builder.append_value(bytes(value)),
+            None => builder.append_null(),
+        }
+    }
+    builder.finish()
+}
+
+/// Gives the bytes that a hasher reads for each value of `s`.
+///
+/// A null stays null. Therefore the hasher gives a null, as it does for a String.
+pub fn canonical_bytes(s: &Series) -> PolarsResult<BinaryChunked> {
+    let out = match s.dtype() {
+        DataType::String => s.str()?.as_binary(),
+        DataType::Binary => s.binary()?.clone(),
+        DataType::Boolean => {
+            let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
+            for value in s.bool()?.iter() {
+                match value {
+                    Some(value) => builder.append_value([u8::from(value)]),
+                    None => builder.append_null(),
+                }
+            }
+            builder.finish()
+        }
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => {
+            fixed_width(s.cast(&DataType::Int64)?.i64()?, i64::to_le_bytes)
+        }
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
+            fixed_width(s.cast(&DataType::UInt64)?.u64()?, u64::to_le_bytes)
+        }
+        DataType::Int128 => fixed_width(s.i128()?, i128::to_le_bytes),
+        DataType::UInt128 => fixed_width(s.u128()?, u128::to_le_bytes),
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            fixed_width(s.cast(&DataType::Float64)?.f64()?, |v| {
+                normalize_float(v).to_le_bytes()
+            })
+        }
+        DataType::Date => fixed_width(s.to_physical_repr().i32()?, i32::to_le_bytes),
+        DataType::Time => fixed_width(s.to_physical_repr().i64()?, i64::to_le_bytes),
+        // A time zone changes the display of a time, but not the time itself.
+        // Nanoseconds from a time in milliseconds can be more than an `i64` holds.
+        // Therefore the count is an `i128`.
+        DataType::Datetime(unit, _) | DataType::Duration(unit) => {
+            let per_unit = per_unit(unit);
+            fixed_width(s.to_physical_repr().i64()?, |v| {
+                (v as i128 * per_unit).to_le_bytes()
+            })
+        }
+        // The physical index of a category depends on the order of the first values,
+        // not on the data. Therefore the string is the value.
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            s.cast(&DataType::String)?.str()?.as_binary()
+        }
+        dtype => return Err(unhashable(dtype)),
+    };
+    Ok(out)
+}
+
+/// Checks that [`canonical_bytes`] reads a column of `dtype`. An output type function
+/// calls this, and therefore a plan with such a column fails before it reads data.
+///
+/// Polars does not know some data types before it runs, such as the result of
+/// `map_elements` without a `return_dtype`. Such a data type passes, and
+/// [`canonical_bytes`] checks the data.
+pub fn check_canonical(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::Unknown(_)
+        | DataType::String
+        | DataType::Binary
+        | DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Int128
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::UInt128
+        | DataType::Float16
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Date
+        | DataType::Time
+        | DataType::Datetime(_, _)
+        | DataType::Duration(_)
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _) => Ok(()),
+        dtype => Err(unhashable(dtype)),
+    }
+}
+
+fn unhashable(dtype: &DataType) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected `String`, `Binary`, a numeric, temporal, boolean, categorical or \
+        `Struct` input, or a `List` or `Array` of one, got `{}`",
+        dtype
+    )
+}
@@ -0,0 +1,167 @@
+//! Changes a column into the bytes of a Cassandra partition key, for `cassandra_token`.
+//!
+//! Cassandra hashes the serialized key, and not the canonical bytes of this crate. Its
+//! numbers are big-endian and keep their own width: an `int` is 4 bytes and a `bigint`
+//! is 8 bytes. Therefore the same value of an `Int32` and of an `Int64` column gives
+//! two different tokens, as it does in Cassandra.
+//!
+//! A key of more than one column is a `Struct`. Cassandra serializes it as a
+//! `CompositeType`: each component is a 2-byte big-endian length, the bytes, and a 0.
+
+use crate::canonical::fixed_width;
+use crate::shared::at_row;
+use polars::prelude::*;
+
+/// The `date` of Cassandra counts the days from 1970-01-01 as an unsigned integer, with
+/// 1970-01-01 at 2^31.
+const DATE_EPOCH: i64 = 1 << 31;
+
+/// Gives the bytes of the partition key of each row of `s`. A null value, and a
+/// `Struct` row with a null field, give null, because a partition key cannot be null.
+pub fn key_bytes(s: &Series) -> PolarsResult<BinaryChunked> {
+    match s.dtype() {
+        DataType::Struct(_) => composite_bytes(s),
+        _ => component_bytes(s),
+    }
+}
+
+/// Checks that [`key_bytes`] reads a column of `dtype`, when polars builds the plan. A
+/// `List` or an `Array` gives a token to each element, as a hasher does.
+pub fn check_key_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::List(inner) | DataType::Array(inner, _) => check_key_dtype(inner),
+        DataType::Struct(fields) if fields.is_empty() => Err(no_key(dtype)),
+        DataType::Struct(fields) => fields
+            .iter()
+            .try_for_each(|field| check_component_dtype(field.dtype())),
+        dtype => check_component_dtype(dtype),
+    }
+}
+
+fn check_component_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::Unknown(_)
+        | DataType::String
+        | DataType::Binary
+        | DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Date
+        | DataType::Time
+        | DataType::Datetime(_, _)
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _) => Ok(()),
+        dtype => Err(no_key(dtype)),
+    }
+}
+
+fn no_key(dtype: &DataType) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected `String`, `Binary`, a signed integer, a float, `Boolean`, `Date`, \
+        `Time`, `Datetime` or categorical input for a Cassandra key, or a `Struct` of \
+        them, got `{}`",
+        dtype
+    )
+}
+
+/// The bytes of one value, as the serializer of its Cassandra type writes them.
+fn component_bytes(s: &Series) -> PolarsResult<BinaryChunked> {
+    let out = match s.dtype() {
+        DataType::String => s.str()?.as_binary(),
+        DataType::Binary => s.binary()?.clone(),
+        DataType::Boolean => {
+            let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
+            for value in s.bool()?.iter() {
+                match value {
+                    Some(value) => builder.append_value([u8::from(value)]),
+                    None => builder.append_null(),
+                }
+            }
+            builder.finish()
+        }
+        DataType::Int8 => fixed_width(s.i8()?, i8::to_be_bytes),
+        DataType::Int16 => fixed_width(s.i16()?, i16::to_be_bytes),
+        DataType::Int32 => fixed_width(s.i32()?, i32::to_be_bytes),
+        DataType::Int64 => fixed_width(s.i64()?, i64::to_be_bytes),
+        DataType::Float32 => fixed_width(s.f32()?, f32::to_be_bytes),
+        DataType::Float64 => fixed_width(s.f64()?, f64::to_be_bytes),
+        DataType::Date => fixed_width(s.to_physical_repr().i32()?, |days| {
+            ((days as i64 + DATE_EPOCH) as u32).to_be_bytes()
+        }),
+        // Both count nanoseconds from midnight.
+        DataType::Time => fixed_width(s.to_physical_repr().i64()?, i64::to_be_bytes),
+        // A `timestamp` counts milliseconds. Java rounds an instant down to them.
+        DataType::Datetime(unit, _) => {
+            let per_milli = match unit {
+                TimeUnit::Nanoseconds => 1_000_000,
+                TimeUnit::Microseconds => 1_000,
+                TimeUnit::Milliseconds => 1,
+            };
+            fixed_width(s.to_physical_repr().i64()?, |v| {
+                v.div_euclid(per_milli).to_be_bytes()
+            })
+        }
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            s.cast(&DataType::String)?.str()?.as_binary()
+        }
+        dtype => return Err(no_key(dtype)),
+    };
+    Ok(out)
+}
+
+fn composite_bytes(s: &Series) -> PolarsResult<BinaryChunked> {
+    let ca = s.struct_()?;
+    let components = ca
+        .fields_as_series()
+        .iter()
+        .map(component_bytes)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    if components.is_empty() {
+        return Err(no_key(s.dtype()));
+    }
+    let components: Vec<BinaryChunked> = components
+        .iter()
+        .map(|c| c.rechunk().into_owned())
+        .collect();
+    let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
+    let mut key = Vec::new();
+    for (row, null) in s.is_null().iter().enumerate() {
+        if null == Some(true) {
+            builder.append_null();
+            continue;
+        }
+        key.clear();
+        let mut complete = true;
+        for component in &components {
+            let Some(bytes) = component.get(row) else {
+                complete = false;
+                break;
+            };
+            let len = u16::try_from(bytes.len()).map_err(|_| {
+                at_row(
+                    polars_err!(
+                        ComputeError:
+                        "a component of a Cassandra key has {} bytes, and the most is 65535",
+                        bytes.len()
+                    ),
+                    row,
+                    component.name(),
+                )
+            })?;
+            key.extend_from_slice(&len.to_be_bytes());
+            key.extend_from_slice(bytes);
+            key.push(0);
+        }
+        if complete {
+            builder.append_value(&key);
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(builder.finish())
+}
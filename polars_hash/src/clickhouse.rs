@@ -0,0 +1,382 @@
+//! The hash functions of ClickHouse, with the rules that ClickHouse uses to read more
+//! than one argument.
+//!
+//! ClickHouse hashes each argument, and it combines each hash into the hash of the
+//! arguments before it. Each function has its own combine step. A `Tuple` argument is
+//! its elements, one after the other. An `Array` argument is the hash of its length,
+//! and then the hash of each element.
+//!
+//! `cityHash64` of ClickHouse is CityHash v1.0.2. The `cityhasher` crate is v1.1, and
+//! the `cityhash-rs` crate has only the 128-bit hash of v1.0.2. Therefore this module
+//! has its own port of the 64-bit hash, as `row_encode` is its own code. The golden
+//! vectors of the tests keep it the same as ClickHouse.
+
+use md5::compute as md5;
+use mur3::murmurhash3_x64_128;
+use polars::prelude::*;
+use siphasher::sip::SipHasher24;
+
+const K0: u64 = 0xc3a5_c85c_97cb_3127;
+const K1: u64 = 0xb492_b66f_be98_f273;
+const K2: u64 = 0x9ae1_6a3b_2f90_404f;
+const K3: u64 = 0xc949_d7c7_509e_6557;
+
+fn fetch_64(value: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(value[at..at + 8].try_into().unwrap())
+}
+
+fn fetch_32(value: &[u8], at: usize) -> u64 {
+    u32::from_le_bytes(value[at..at + 4].try_into().unwrap()) as u64
+}
+
+fn shift_mix(value: u64) -> u64 {
+    value ^ (value >> 47)
+}
+
+/// `Hash128to64` of CityHash. ClickHouse also uses it to combine two `cityHash64`
+/// hashes.
+fn hash_128_to_64(low: u64, high: u64) -> u64 {
+    const K_MUL: u64 = 0x9ddf_ea08_eb38_2d69;
+    let a = shift_mix((low ^ high).wrapping_mul(K_MUL));
+    let b = shift_mix((high ^ a).wrapping_mul(K_MUL));
+    b.wrapping_mul(K_MUL)
+}
+
+fn hash_len_0_to_16(value: &[u8]) -> u64 {
+    let len = value.len();
+    if len > 8 {
+        let a = fetch_64(value, 0);
+        let b = fetch_64(value, len - 8);
+        return hash_128_to_64(a, b.wrapping_add(len as u64).rotate_right(len as u32)) ^ b;
+    }
+    if len >= 4 {
+        let a = fetch_32(value, 0);
+        return hash_128_to_64((len as u64).wrapping_add(a << 3), fetch_32(value, len - 4));
+    }
+    if len > 0 {
+        let y = value[0] as u32 + ((value[len >> 1] as u32) << 8);
+        let z = len as u32 + ((value[len - 1] as u32) << 2);
+        return shift_mix((y as u64).wrapping_mul(K2) ^ (z as u64).wrapping_mul(K3))
+            .wrapping_mul(K2);
+    }
+    K2
+}
+
+fn hash_len_17_to_32(value: &[u8]) -> u64 {
+    let len = value.len();
+    let a = fetch_64(value, 0).wrapping_mul(K1);
+    let b = fetch_64(value, 8);
+    let c = fetch_64(value, len - 8).wrapping_mul(K2);
+    let d = fetch_64(value, len - 16).wrapping_mul(K0);
+    hash_128_to_64(
+        a.wrapping_sub(b)
+            .rotate_right(43)
+            .wrapping_add(c.rotate_right(30))
+            .wrapping_add(d),
+        a.wrapping_add((b ^ K3).rotate_right(20))
+            .wrapping_sub(c)
+            .wrapping_add(len as u64),
+    )
+}
+
+fn weak_hash_len_32_with_seeds(value: &[u8], at: usize, mut a: u64, b: u64) -> (u64, u64) {
+    let (w, x) = (fetch_64(value, at), fetch_64(value, at + 8));
+    let (y, z) = (fetch_64(value, at + 16), fetch_64(value, at + 24));
+    a = a.wrapping_add(w);
+    let mut b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+fn hash_len_33_to_64(value: &[u8]) -> u64 {
+    let len = value.len();
+    let mut z = fetch_64(value, 24);
+    let mut a = fetch_64(value, 0).wrapping_add(
+        (len as u64)
+            .wrapping_add(fetch_64(value, len - 16))
+            .wrapping_mul(K0),
+    );
+    let mut b = a.wrapping_add(z).rotate_right(52);
+    let mut c = a.rotate_right(37);
+    a = a.wrapping_add(fetch_64(value, 8));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch_64(value, 16));
+    let vf = a.wrapping_add(z);
+    let vs = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+    a = fetch_64(value, 16).wrapping_add(fetch_64(value, len - 32));
+    z = fetch_64(value, len - 8);
+    b = a.wrapping_add(z).rotate_right(52);
+    c = a.rotate_right(37);
+    a = a.wrapping_add(fetch_64(value, len - 24));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch_64(value, len - 16));
+    let wf = a.wrapping_add(z);
+    let ws = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+    let r = shift_mix(
+        vf.wrapping_add(ws)
+            .wrapping_mul(K2)
+            .wrapping_add(wf.wrapping_add(vs).wrapping_mul(K0)),
+    );
+    shift_mix(r.wrapping_mul(K0).wrapping_add(vs)).wrapping_mul(K2)
+}
+
+/// `CityHash64` of CityHash v1.0.2.
+pub fn cityhash64_v102(value: &[u8]) -> u64 {
+    let len = value.len();
+    if len <= 16 {
+        return hash_len_0_to_16(value);
+    }
+    if len <= 32 {
+        return hash_len_17_to_32(value);
+    }
+    if len <= 64 {
+        return hash_len_33_to_64(value);
+    }
+
+    // A value of more than 64 bytes hashes its end first. The loop then keeps 56 bytes
+    // of state in `v`, `w`, `x`, `y` and `z`.
+    let mut x = fetch_64(value, 0);
+    let mut y = fetch_64(value, len - 16) ^ K1;
+    let mut z = fetch_64(value, len - 56) ^ K0;
+    let mut v = weak_hash_len_32_with_seeds(value, len - 64, len as u64, y);
+    let mut w = weak_hash_len_32_with_seeds(value, len - 32, (len as u64).wrapping_mul(K1), K0);
+    z = z.wrapping_add(shift_mix(v.1).wrapping_mul(K1));
+    x = z.wrapping_add(x).rotate_right(39).wrapping_mul(K1);
+    y = y.rotate_right(33).wrapping_mul(K1);
+
+    // The loop reads the blocks of 64 bytes. The last block ends at the last multiple
+    // of 64 before the end, and the bytes after it were hashed above.
+    for at in (0..(len - 1) & !63).step_by(64) {
+        x = x
+            .wrapping_add(y)
+            .wrapping_add(v.0)
+            .wrapping_add(fetch_64(value, at + 16))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        y = y
+            .wrapping_add(v.1)
+            .wrapping_add(fetch_64(value, at + 48))
+            .rotate_right(42)
+            .wrapping_mul(K1);
+        x ^= w.1;
+        y ^= v.0;
+        z = (z ^ w.0).rotate_right(33);
+        v = weak_hash_len_32_with_seeds(value, at, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len_32_with_seeds(value, at + 32, z.wrapping_add(w.1), y);
+        std::mem::swap(&mut z, &mut x);
+    }
+    hash_128_to_64(
+        hash_128_to_64(v.0, w.0)
+            .wrapping_add(shift_mix(y).wrapping_mul(K1))
+            .wrapping_add(z),
+        hash_128_to_64(v.1, w.1).wrapping_add(x),
+    )
+}
+
+/// `intHash64` of ClickHouse, with the constant of `IntHash64Impl`. ClickHouse hashes
+/// the length of an array with it, and an integer for `cityHash64`.
+fn int_hash64(value: u64) -> u64 {
+    let mut x = value ^ 0x4cf2_d2ba_ae6d_a887;
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    x ^ (x >> 33)
+}
+
+/// The bytes of two hashes, for a function that combines by a hash of them.
+fn two_hashes(first: u64, second: u64) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&first.to_le_bytes());
+    bytes[8..].copy_from_slice(&second.to_le_bytes());
+    bytes
+}
+
+/// A hash function of ClickHouse that gives a `UInt64`.
+#[derive(Clone, Copy)]
+pub enum Function {
+    CityHash64,
+    SipHash64,
+    MurmurHash3_64,
+    HalfMd5,
+}
+
+impl Function {
+    /// The hash of the bytes of one value.
+    fn apply(self, value: &[u8]) -> u64 {
+        match self {
+            Function::CityHash64 => cityhash64_v102(value),
+            Function::SipHash64 => SipHasher24::new().hash(value),
+            // ClickHouse takes the XOR of the two halves of the 128-bit hash.
+            Function::MurmurHash3_64 => {
+                let (h1, h2) = murmurhash3_x64_128(value, 0);
+                h1 ^ h2
+            }
+            // The first 8 bytes of the digest, in big-endian order.
+            Function::HalfMd5 => u64::from_be_bytes(md5(value).0[..8].try_into().unwrap()),
+        }
+    }
+
+    /// Combines the hash of the arguments so far with the hash of the next argument.
+    fn combine(self, hash: u64, next: u64) -> u64 {
+        match self {
+            Function::CityHash64 => hash_128_to_64(hash, next),
+            Function::MurmurHash3_64 => int_hash64(hash) ^ next,
+            Function::SipHash64 | Function::HalfMd5 => self.apply(&two_hashes(hash, next)),
+        }
+    }
+
+    /// The hash of an integer. `cityHash64` hashes the integer with `intHash64`, and
+    /// its bits are not sign-extended to 64 bits. The others hash the bytes of the
+    /// integer at its own width.
+    fn integer<const N: usize>(self, bytes: [u8; N]) -> u64 {
+        match self {
+            Function::CityHash64 => {
+                let mut wide = [0; 8];
+                wide[..N].copy_from_slice(&bytes);
+                int_hash64(u64::from_le_bytes(wide))
+            }
+            _ => self.apply(&bytes),
+        }
+    }
+}
+
+/// Checks that [`clickhouse_hashes`] reads a column of `dtype`, when polars builds the
+/// plan.
+pub fn check_clickhouse_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::List(inner) | DataType::Array(inner, _) => check_clickhouse_dtype(inner),
+        DataType::Struct(fields) if fields.is_empty() => Err(no_clickhouse_type(dtype)),
+        DataType::Struct(fields) => fields
+            .iter()
+            .try_for_each(|field| check_clickhouse_dtype(field.dtype())),
+        DataType::Unknown(_)
+        | DataType::String
+        | DataType::Binary
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _)
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => Ok(()),
+        dtype => Err(no_clickhouse_type(dtype)),
+    }
+}
+
+fn no_clickhouse_type(dtype: &DataType) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected `String`, `Binary`, an integer of 64 bits or less, or a `Struct`, \
+        `List` or `Array` of them, for a ClickHouse hash, got `{}`",
+        dtype
+    )
+}
+
+/// The hash of each value of one column that is not nested.
+fn value_hashes(s: &Series, f: Function) -> PolarsResult<Vec<Option<u64>>> {
+    fn each<T: PolarsNumericType, const N: usize>(
+        ca: &ChunkedArray<T>,
+        f: Function,
+        bytes: impl Fn(T::Native) -> [u8; N],
+    ) -> Vec<Option<u64>> {
+        ca.iter().map(|v| v.map(|v| f.integer(bytes(v)))).collect()
+    }
+    let bytes = |ca: &BinaryChunked| ca.iter().map(|v| v.map(|v| f.apply(v))).collect();
+    let out = match s.dtype() {
+        DataType::String => bytes(&s.str()?.as_binary()),
+        DataType::Binary => bytes(s.binary()?),
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            bytes(&s.cast(&DataType::String)?.str()?.as_binary())
+        }
+        DataType::Int8 => each(s.i8()?, f, i8::to_le_bytes),
+        DataType::Int16 => each(s.i16()?, f, i16::to_le_bytes),
+        DataType::Int32 => each(s.i32()?, f, i32::to_le_bytes),
+        DataType::Int64 => each(s.i64()?, f, i64::to_le_bytes),
+        DataType::UInt8 => each(s.u8()?, f, u8::to_le_bytes),
+        DataType::UInt16 => each(s.u16()?, f, u16::to_le_bytes),
+        DataType::UInt32 => each(s.u32()?, f, u32::to_le_bytes),
+        DataType::UInt64 => each(s.u64()?, f, u64::to_le_bytes),
+        dtype => return Err(no_clickhouse_type(dtype)),
+    };
+    Ok(out)
+}
+
+/// Adds the hashes of the argument `s` to `hashes`, the hashes of the arguments before
+/// it. `hashes` is `None` for the first argument. A null value makes a null row.
+fn add_argument(
+    s: &Series,
+    f: Function,
+    hashes: &mut Option<Vec<Option<u64>>>,
+) -> PolarsResult<()> {
+    let add = |hashes: &mut Option<Vec<Option<u64>>>, next: Vec<Option<u64>>| {
+        *hashes = Some(match hashes.take() {
+            None => next,
+            Some(before) => before
+                .into_iter()
+                .zip(next)
+                .map(|(hash, next)| Some(f.combine(hash?, next?)))
+                .collect(),
+        });
+    };
+    match s.dtype() {
+        // A `Tuple` is its elements, as if each one were an argument of its own.
+        DataType::Struct(_) => {
+            for field in s.struct_()?.fields_as_series() {
+                add_argument(&field, f, hashes)?;
+            }
+            if s.null_count() > 0 {
+                let rows = hashes.as_mut().expect("a Struct has a field");
+                for (hash, null) in rows.iter_mut().zip(s.is_null().iter()) {
+                    if null == Some(true) {
+                        *hash = None;
+                    }
+                }
+            }
+        }
+        // An element of an `Array` is hashed as a first argument, and not combined
+        // with the arguments before the array. Therefore the values of the column are
+        // hashed alone, and each row combines its own.
+        DataType::List(inner) | DataType::Array(inner, _) => {
+            let s = s.cast(&DataType::List(inner.clone()))?.rechunk();
+            let ca = s.list()?;
+            let arr = ca
+                .downcast_get(0)
+                .expect("a rechunked column has one chunk");
+            let offsets = arr.offsets();
+            let (first, last) = (*offsets.first(), *offsets.last());
+            let values = ca.get_inner().slice(first, (last - first) as usize);
+            let elements = column_hashes(&values, f)?;
+            let next = offsets
+                .windows(2)
+                .zip(ca.is_null().iter())
+                .map(|(bounds, null)| {
+                    if null == Some(true) {
+                        return None;
+                    }
+                    let (start, end) = ((bounds[0] - first) as usize, (bounds[1] - first) as usize);
+                    let length = int_hash64((end - start) as u64);
+                    elements[start..end]
+                        .iter()
+                        .try_fold(length, |hash, element| Some(f.combine(hash, (*element)?)))
+                })
+                .collect();
+            add(hashes, next);
+        }
+        _ => add(hashes, value_hashes(s, f)?),
+    }
+    Ok(())
+}
+
+/// Gives the hash of each row of `s`, as the ClickHouse function `f` gives it for the
+/// same arguments. A Struct is more than one argument.
+pub fn column_hashes(s: &Series, f: Function) -> PolarsResult<Vec<Option<u64>>> {
+    let mut hashes = None;
+    add_argument(s, f, &mut hashes)?;
+    Ok(hashes.unwrap_or_else(|| vec![None; s.len()]))
+}
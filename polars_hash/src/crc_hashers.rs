@@ -0,0 +1,52 @@
+//! CRC checksums that a data store uses to place a key.
+
+/// The number of hash slots of a Redis Cluster.
+const REDIS_SLOTS: u16 = 16384;
+
+/// The table of CRC16-XMODEM: polynomial 0x1021, start value 0, no reflection and no
+/// final XOR. Redis gives this as "CRC16/XMODEM" in the cluster specification.
+const CRC16_TABLE: [u16; 256] = {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+pub fn crc16_xmodem(value: &[u8]) -> u16 {
+    value.iter().fold(0, |crc, &b| {
+        (crc << 8) ^ CRC16_TABLE[((crc >> 8) as u8 ^ b) as usize]
+    })
+}
+
+/// The part of a key that Redis Cluster hashes. If the key has a `{`, and a `}` after
+/// it with at least one byte between them, Redis hashes only those bytes. This is the
+/// hash tag. Only the first `{` counts, so `foo{}{bar}` has no hash tag.
+fn redis_hash_tag(key: &[u8]) -> &[u8] {
+    if let Some(open) = key.iter().position(|&b| b == b'{') {
+        let rest = &key[open + 1..];
+        if let Some(close) = rest.iter().position(|&b| b == b'}') {
+            if close > 0 {
+                return &rest[..close];
+            }
+        }
+    }
+    key
+}
+
+/// `CLUSTER KEYSLOT` of Redis: the CRC16 of the hash tag, modulo 16384.
+pub fn redis_cluster_slot(key: &[u8]) -> u16 {
+    crc16_xmodem(redis_hash_tag(key)) % REDIS_SLOTS
+}
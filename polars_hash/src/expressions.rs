@@ -1,20 +1,41 @@
-use crate::geohashers::{geohash_decoder, geohash_encoder, geohash_neighbors};
-use crate::h3::h3_encoder;
+use crate::affix::Affixed;
+use crate::cassandra::{check_key_dtype, key_bytes};
+use crate::clickhouse::{check_clickhouse_dtype, column_hashes, Function};
+use crate::crc_hashers::redis_cluster_slot;
+use crate::geohashers::{geohash_decoder, geohash_encoder, geohash_neighbors, validate_length};
+use crate::h3::{h3_encoder, validate_resolution};
 use crate::hmac_hashers::*;
+use crate::iceberg::{self, check_bucket_dtype, truncated_dtype};
+use crate::input_encoding::{InputEncoding, OnDecodeError};
+use crate::jvm::{
+    check_funnel_dtype, check_hash_code_dtype, check_hive_dtype, funnel_bytes, hash_codes,
+    hive_buckets,
+};
 use crate::murmurhash_hashers::*;
+use crate::postgres::{check_partition_key_dtype, partition_remainders};
+use crate::registry::{
+    digests_dtype, hash_all, lookup, lookup_all, Algorithm, Hasher, Options, Seed,
+};
+use crate::row_encode::{check_row_dtype, check_version};
 use crate::sha_hashers::*;
+use crate::sha_lanes::Sha;
 use crate::shared::{
-    float_arg, hash_bytes, hash_bytes_into_binary, hash_bytes_into_string, integer_arg, scalar_arg,
+    check_coords_field, check_seed_field, check_string_field, float_arg, hash_batches_into_string,
+    hash_bytes, hash_bytes_into_binary, hash_bytes_into_string, hash_bytes_seeded,
+    hash_bytes_seeded_into_binary, hash_elements, hashed_dtype, integer_arg, or_null, scalar_arg,
+    signed_dtype, with_sign, write_hex, InputKwargs, Rows,
 };
+use crate::spark::{self, check_spark_dtype, row_hashes};
 use crate::timehashers::{
-    epoch_seconds, hash_column, timehash_decoder, timehash_encoder, timehash_neighbors,
-    validate_precision,
+    check_epoch_dtype, epoch_seconds, hash_column, timehash_decoder, timehash_encoder,
+    timehash_neighbors, validate_precision,
 };
 use crate::xxhash_hashers::*;
 use hmac::KeyInit;
 use polars::{
     chunked_array::ops::arity::{
         try_binary_elementwise, try_ternary_elementwise, try_unary_elementwise,
+        unary_elementwise_values,
     },
     prelude::*,
 };
@@ -24,14 +45,17 @@ use polars_core::datatypes::{
     Field,
 };
 use pyo3_polars::derive::polars_expr;
+use pyo3_polars::export::polars_core::runtime::RAYON;
 use serde::Deserialize;
-use std::fmt::Write;
 use std::{str, string};
 use wyhash::wyhash as real_wyhash;
 
 #[derive(Deserialize)]
 struct SeedKwargs32bit {
     seed: u32,
+    signed: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
 /// Kwargs arrive as a pickle, whose integers are `i64`, so the Python side sends a
@@ -39,6 +63,9 @@ struct SeedKwargs32bit {
 #[derive(Deserialize)]
 struct SeedKwargs64bit {
     seed: i64,
+    signed: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
 /// A 32-bit seed and the choice of output data type, for a hasher of 128 bits.
@@ -46,6 +73,9 @@ struct SeedKwargs64bit {
 struct Seed32AndBinaryKwargs {
     seed: u32,
     return_binary: bool,
+    signed: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
 /// A 64-bit seed and the choice of output data type, for a hasher of 128 bits. The
@@ -54,6 +84,9 @@ struct Seed32AndBinaryKwargs {
 struct Seed64AndBinaryKwargs {
     seed: i64,
     return_binary: bool,
+    signed: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
 /// What [`Seed64AndBinaryKwargs`] holds, and the byte order of a binary output. XXH3
@@ -65,14 +98,38 @@ struct Xxh3Kwargs {
     seed: i64,
     return_binary: bool,
     big_endian: bool,
+    signed: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
-/// The choice of output data type alone. Every 128-bit hasher sends this kwarg, and
-/// each one sends a seed of its own width, or no seed. Serde reads the field it knows
+/// The choice of output data type alone. Every 128-bit hasher sends these kwargs, and
+/// each one sends a seed of its own width, or no seed. Serde reads the fields it knows
 /// and passes over the rest, so one struct serves all of them.
 #[derive(Deserialize)]
 struct BinaryKwargs {
     return_binary: bool,
+    signed: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
+}
+
+/// The choice of a signed output, for a hasher of 32 or 64 bits. The unseeded hashers
+/// take this as their kwargs. As with [`BinaryKwargs`], the output type functions read
+/// the field from the kwargs of each hasher, and pass over the seed.
+#[derive(Deserialize)]
+struct SignedKwargs {
+    signed: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
+}
+
+/// The number of partitions of a Kafka topic.
+#[derive(Deserialize)]
+struct KafkaKwargs {
+    partitions: i64,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
 /// Gives the data type that a 128-bit hasher writes.
@@ -82,40 +139,168 @@ struct BinaryKwargs {
 /// this function as well, which `output_type_func_with_kwargs` does. The name follows
 /// the first input column, which is what a plain `output_type` declaration does.
 fn hash_128_output(fields: &[Field], kwargs: BinaryKwargs) -> PolarsResult<Field> {
-    let dtype = if kwargs.return_binary {
-        DataType::Binary
-    } else {
-        DataType::UInt128
+    check_hasher_fields(fields, &kwargs.input)?;
+    let dtype = match (kwargs.return_binary, kwargs.signed) {
+        (true, true) => polars_bail!(InvalidOperation: "a binary output has no sign"),
+        (true, false) => DataType::Binary,
+        (false, signed) => signed_output(DataType::UInt128, signed),
     };
-    Ok(Field::new(fields[0].name().clone(), dtype))
+    hashed_output(fields, dtype)
+}
+
+/// Gives `dtype`, or the signed type of its width if the caller asks for a sign.
+fn signed_output(dtype: DataType, signed: bool) -> DataType {
+    if signed {
+        signed_dtype(dtype)
+    } else {
+        dtype
+    }
+}
+
+/// Gives the field of a hasher that writes `dtype`. A List or an Array input gives a
+/// List of the same depth, because [`hash_elements`] hashes the elements.
+fn hashed_output(fields: &[Field], dtype: DataType) -> PolarsResult<Field> {
+    Ok(Field::new(
+        fields[0].name().clone(),
+        hashed_dtype(fields[0].dtype(), dtype),
+    ))
+}
+
+/// Checks the inputs of a hasher and the kwargs of its input column, before polars
+/// reads any data. The output type functions below call this, and therefore a wrong
+/// input fails in `collect_schema`, and not after the I/O of the query.
+fn check_hasher_fields(fields: &[Field], input: &InputKwargs) -> PolarsResult<()> {
+    input.check_fields(fields)?;
+    check_seed_field(fields, input)
+}
+
+/// The kwargs of a digest without a seed. Serde passes over the key of HMAC, and reads
+/// the fields of the input column.
+fn string_output(fields: &[Field], kwargs: InputKwargs) -> PolarsResult<Field> {
+    kwargs.check_fields(fields)?;
+    hashed_output(fields, DataType::String)
+}
+
+fn hash_32_output(fields: &[Field], kwargs: SignedKwargs) -> PolarsResult<Field> {
+    check_hasher_fields(fields, &kwargs.input)?;
+    hashed_output(fields, signed_output(DataType::UInt32, kwargs.signed))
+}
+
+fn hash_64_output(fields: &[Field], kwargs: SignedKwargs) -> PolarsResult<Field> {
+    check_hasher_fields(fields, &kwargs.input)?;
+    hashed_output(fields, signed_output(DataType::UInt64, kwargs.signed))
+}
+
+fn shake_output(fields: &[Field], kwargs: LengthKwargs) -> PolarsResult<Field> {
+    check_xof_length(kwargs.length)?;
+    string_output(fields, kwargs.input)
 }
 
 #[derive(Deserialize)]
 struct LengthKwargs {
     length: usize,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
 #[derive(Deserialize)]
 struct HmacKwargs {
     key: string::String,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
+/// `strict` raises the error of an invalid row. Otherwise the row gives a null.
 #[derive(Deserialize)]
 struct StrictKwargs {
     strict: bool,
 }
 
+/// `precision` is the precision if the caller gave an integer. Otherwise the second
+/// input holds it. See [`length_arg`].
+#[derive(Deserialize)]
+struct PrecisionKwargs {
+    strict: bool,
+    precision: Option<i64>,
+}
+
+/// The length of a geohash or the resolution of an H3 cell, if the caller gave an
+/// integer. Otherwise the second input holds it. See [`length_arg`].
+#[derive(Deserialize)]
+struct CoordsKwargs {
+    strict: bool,
+    length: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct Uuid5Kwargs {
+    strict: bool,
+    #[serde(flatten)]
+    input: InputKwargs,
+}
+
 #[derive(Deserialize)]
 struct VersionKwargs {
     version: u64,
 }
 
-pub fn blake3_hash(value: &[u8], output: &mut string::String) {
-    write!(output, "{}", blake3::hash(value)).unwrap()
+/// The modulus of the hash partitions of a PostgreSQL table.
+#[derive(Deserialize)]
+struct PostgresKwargs {
+    modulus: i64,
+}
+
+/// The number of buckets of a Hive table or of an Iceberg `bucket[N]` transform.
+#[derive(Deserialize)]
+struct BucketKwargs {
+    buckets: i64,
+}
+
+/// The width of an Iceberg `truncate[W]` transform.
+#[derive(Deserialize)]
+struct TruncateKwargs {
+    width: i64,
 }
 
-pub fn md5_hash(value: &[u8], output: &mut string::String) {
-    write!(output, "{:x}", md5::compute(value)).unwrap()
+/// The seed of a Guava hash function, and the charset of each `putString`.
+#[derive(Deserialize)]
+struct GuavaKwargs {
+    seed: i64,
+    #[serde(default)]
+    input_encoding: InputEncoding,
+    #[serde(default)]
+    on_decode_error: OnDecodeError,
+}
+
+/// A value of this size or more hashes on more than one thread. BLAKE3 is a tree, and
+/// its chunks hash in any order to the same digest. The `blake3` documentation gives
+/// 128 KiB as the size where the threads start to pay for themselves.
+const BLAKE3_BYTES_FOR_THREADS: usize = 128 * 1024;
+
+pub fn blake3_hash(value: Affixed, output: &mut string::String) {
+    let mut hasher = blake3::Hasher::new();
+    if value.len() < BLAKE3_BYTES_FOR_THREADS {
+        for part in value.parts() {
+            hasher.update(part);
+        }
+    } else {
+        // The polars pool, as in `row_encode`. A row task of that pool that gets here
+        // adds the chunks to its own pool, and starts no new threads.
+        RAYON.install(|| {
+            for part in value.parts() {
+                hasher.update_rayon(part);
+            }
+        });
+    }
+    write_hex(hasher.finalize().as_bytes(), output)
+}
+
+pub fn md5_hash(value: Affixed, output: &mut string::String) {
+    let mut context = md5::Context::new();
+    for part in value.parts() {
+        context.consume(part);
+    }
+    write_hex(&context.finalize().0, output)
 }
 
 // `cityhasher::hash` picks the algorithm from its return type alone, so the turbofish
@@ -142,8 +327,10 @@ fn cityhash_128(value: &[u8]) -> u128 {
     cityhash_rs::cityhash_110_128(value)
 }
 
-// gxhash seeds are `i64`, the type kwargs already travel in, so these pass
-// `kwargs.seed` through where the other hashers cast it back to `u64`.
+// gxhash seeds are `i64`. The Python side takes a `u64` seed like the other hashers,
+// from a kwarg or from a column, and the expressions below read it as `u64` for the
+// same reason. `seed as i64` then gives gxhash the two's-complement value that the
+// kwarg travelled as.
 fn gxhash_32(value: &[u8], seed: i64) -> u32 {
     gxhash::gxhash32(value, seed)
 }
@@ -156,201 +343,436 @@ fn gxhash_128(value: &[u8], seed: i64) -> u128 {
     gxhash::gxhash128(value, seed)
 }
 
-#[polars_expr(output_type=UInt32)]
-fn farmhash32(inputs: &[Series]) -> PolarsResult<Series> {
-    let out: UInt32Chunked = hash_bytes(&inputs[0], farmhash::fingerprint32)?;
-    Ok(out.into_series())
+pub static ALGORITHMS: &[Algorithm] = &[
+    Algorithm {
+        name: "blake3",
+        seed: Seed::None,
+        hasher: Hasher::Hex(blake3_hash),
+    },
+    Algorithm {
+        name: "md5",
+        seed: Seed::None,
+        hasher: Hasher::Hex(md5_hash),
+    },
+    Algorithm {
+        name: "wyhash",
+        seed: Seed::None,
+        hasher: Hasher::U64(|v, _| real_wyhash(v, 0)),
+    },
+    Algorithm {
+        name: "farmhash32",
+        seed: Seed::None,
+        hasher: Hasher::U32(|v, _| farmhash::fingerprint32(v)),
+    },
+    Algorithm {
+        name: "farmhash64",
+        seed: Seed::None,
+        hasher: Hasher::U64(|v, _| farmhash::fingerprint64(v)),
+    },
+    Algorithm {
+        name: "cityhash32",
+        seed: Seed::None,
+        hasher: Hasher::U32(|v, _| cityhash_32(v)),
+    },
+    Algorithm {
+        name: "cityhash64",
+        seed: Seed::None,
+        hasher: Hasher::U64(|v, _| cityhash_64(v)),
+    },
+    Algorithm {
+        name: "cityhash64_with_seed",
+        seed: Seed::U64,
+        hasher: Hasher::U64(cityhash_64_with_seed),
+    },
+    Algorithm {
+        name: "cityhash128",
+        seed: Seed::None,
+        hasher: Hasher::U128(|v, _| cityhash_128(v)),
+    },
+    Algorithm {
+        name: "gxhash32",
+        seed: Seed::U64,
+        hasher: Hasher::U32(|v, seed| gxhash_32(v, seed as i64)),
+    },
+    Algorithm {
+        name: "gxhash64",
+        seed: Seed::U64,
+        hasher: Hasher::U64(|v, seed| gxhash_64(v, seed as i64)),
+    },
+    Algorithm {
+        name: "gxhash128",
+        seed: Seed::U64,
+        hasher: Hasher::U128(|v, seed| gxhash_128(v, seed as i64)),
+    },
+];
+
+/// The name of the algorithm, and the arguments that [`Options`] reads. The Python
+/// side sends each argument as its own kwarg, so serde reads the two parts from one
+/// flat map.
+#[derive(Deserialize)]
+struct HashKwargs {
+    algorithm: string::String,
+    #[serde(flatten)]
+    options: Options,
+    #[serde(flatten)]
+    input: InputKwargs,
+}
+
+/// Gives the data type of the algorithm that the kwarg names. An unknown name or an
+/// argument that the algorithm does not read therefore fails when polars builds the
+/// plan, and not when the first batch arrives.
+fn hash_output(fields: &[Field], kwargs: HashKwargs) -> PolarsResult<Field> {
+    let algorithm = lookup(&kwargs.algorithm)?;
+    algorithm.check(&kwargs.options)?;
+    kwargs.input.check_fields(fields)?;
+    hashed_output(fields, algorithm.dtype(&kwargs.options))
+}
+
+#[polars_expr(output_type_func_with_kwargs=hash_output)]
+fn hash(inputs: &[Series], kwargs: HashKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    let algorithm = lookup(&kwargs.algorithm)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        algorithm.hash(s, &kwargs.options, &input)
+    })
 }
 
-#[polars_expr(output_type=UInt64)]
-fn farmhash64(inputs: &[Series]) -> PolarsResult<Series> {
-    let out: UInt64Chunked = hash_bytes(&inputs[0], farmhash::fingerprint64)?;
-    Ok(out.into_series())
+#[derive(Deserialize)]
+struct MultiDigestKwargs {
+    algorithms: Vec<string::String>,
+    #[serde(flatten)]
+    input: InputKwargs,
 }
 
-#[polars_expr(output_type=UInt32)]
-fn cityhash32(inputs: &[Series]) -> PolarsResult<Series> {
-    let out: UInt32Chunked = hash_bytes(&inputs[0], cityhash_32)?;
-    Ok(out.into_series())
+/// Gives the Struct of `multi_digest`. As in `hash_output`, an unknown name fails
+/// when polars builds the plan.
+fn multi_digest_output(fields: &[Field], kwargs: MultiDigestKwargs) -> PolarsResult<Field> {
+    kwargs.input.check_fields(fields)?;
+    hashed_output(fields, digests_dtype(&lookup_all(&kwargs.algorithms)?))
 }
 
-#[polars_expr(output_type=UInt64)]
-fn cityhash64(inputs: &[Series]) -> PolarsResult<Series> {
-    let out: UInt64Chunked = hash_bytes(&inputs[0], cityhash_64)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=multi_digest_output)]
+fn multi_digest(inputs: &[Series], kwargs: MultiDigestKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    let algorithms = lookup_all(&kwargs.algorithms)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_all(s, &algorithms, &input)?.into_series())
+    })
+}
+
+#[polars_expr(output_type_func_with_kwargs=hash_32_output)]
+fn farmhash32(inputs: &[Series], kwargs: SignedKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: UInt32Chunked = hash_bytes(s, &input, farmhash::fingerprint32)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
+}
+
+#[polars_expr(output_type_func_with_kwargs=hash_64_output)]
+fn farmhash64(inputs: &[Series], kwargs: SignedKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: UInt64Chunked = hash_bytes(s, &input, farmhash::fingerprint64)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
+}
+
+#[polars_expr(output_type_func_with_kwargs=hash_32_output)]
+fn cityhash32(inputs: &[Series], kwargs: SignedKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: UInt32Chunked = hash_bytes(s, &input, cityhash_32)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
+}
+
+#[polars_expr(output_type_func_with_kwargs=hash_64_output)]
+fn cityhash64(inputs: &[Series], kwargs: SignedKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: UInt64Chunked = hash_bytes(s, &input, cityhash_64)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
 /// Its own expression rather than a seed defaulting to 0, because
 /// `CityHash64WithSeed(v, 0)` is a different hash from `CityHash64(v)`.
-#[polars_expr(output_type=UInt64)]
+#[polars_expr(output_type_func_with_kwargs=hash_64_output)]
 fn cityhash64_with_seed(inputs: &[Series], kwargs: SeedKwargs64bit) -> PolarsResult<Series> {
-    let seed = kwargs.seed as u64;
-    let out: UInt64Chunked = hash_bytes(&inputs[0], |v| cityhash_64_with_seed(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        let out: UInt64Chunked = hash_bytes_seeded::<_, UInt64Type, _, _>(
+            s,
+            &input,
+            seeds,
+            kwargs.seed as u64,
+            cityhash_64_with_seed,
+        )?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
 #[polars_expr(output_type_func_with_kwargs=hash_128_output)]
 fn cityhash128(inputs: &[Series], kwargs: BinaryKwargs) -> PolarsResult<Series> {
-    if kwargs.return_binary {
-        let out = hash_bytes_into_binary(&inputs[0], |v| cityhash_128(v).to_le_bytes())?;
-        return Ok(out.into_series());
-    }
-    let out: UInt128Chunked = hash_bytes(&inputs[0], cityhash_128)?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        if kwargs.return_binary {
+            let out = hash_bytes_into_binary(s, &input, |v| cityhash_128(v).to_le_bytes())?;
+            return Ok(out.into_series());
+        }
+        let out: UInt128Chunked = hash_bytes(s, &input, cityhash_128)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
-#[polars_expr(output_type=UInt32)]
+#[polars_expr(output_type_func_with_kwargs=hash_32_output)]
 fn gxhash32(inputs: &[Series], kwargs: SeedKwargs64bit) -> PolarsResult<Series> {
-    let seed = kwargs.seed;
-    let out: UInt32Chunked = hash_bytes(&inputs[0], |v| gxhash_32(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        let out: UInt32Chunked = hash_bytes_seeded::<_, UInt64Type, _, _>(
+            s,
+            &input,
+            seeds,
+            kwargs.seed as u64,
+            |v, seed| gxhash_32(v, seed as i64),
+        )?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
-#[polars_expr(output_type=UInt64)]
+#[polars_expr(output_type_func_with_kwargs=hash_64_output)]
 fn gxhash64(inputs: &[Series], kwargs: SeedKwargs64bit) -> PolarsResult<Series> {
-    let seed = kwargs.seed;
-    let out: UInt64Chunked = hash_bytes(&inputs[0], |v| gxhash_64(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        let out: UInt64Chunked = hash_bytes_seeded::<_, UInt64Type, _, _>(
+            s,
+            &input,
+            seeds,
+            kwargs.seed as u64,
+            |v, seed| gxhash_64(v, seed as i64),
+        )?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
 #[polars_expr(output_type_func_with_kwargs=hash_128_output)]
 fn gxhash128(inputs: &[Series], kwargs: Seed64AndBinaryKwargs) -> PolarsResult<Series> {
-    let seed = kwargs.seed;
-    if kwargs.return_binary {
-        let out = hash_bytes_into_binary(&inputs[0], |v| gxhash_128(v, seed).to_le_bytes())?;
-        return Ok(out.into_series());
-    }
-    let out: UInt128Chunked = hash_bytes(&inputs[0], |v| gxhash_128(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    let seed = kwargs.seed as u64;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        if kwargs.return_binary {
+            let out = hash_bytes_seeded_into_binary::<_, UInt64Type, _>(
+                s,
+                &input,
+                seeds,
+                seed,
+                |v, seed| gxhash_128(v, seed as i64).to_le_bytes(),
+            )?;
+            return Ok(out.into_series());
+        }
+        let out: UInt128Chunked =
+            hash_bytes_seeded::<_, UInt64Type, _, _>(s, &input, seeds, seed, |v, seed| {
+                gxhash_128(v, seed as i64)
+            })?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
-#[polars_expr(output_type=UInt64)]
-fn wyhash(inputs: &[Series]) -> PolarsResult<Series> {
-    let out: UInt64Chunked = hash_bytes(&inputs[0], |v| real_wyhash(v, 0))?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=hash_64_output)]
+fn wyhash(inputs: &[Series], kwargs: SignedKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: UInt64Chunked = hash_bytes(s, &input, |v| real_wyhash(v, 0))?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn blake3(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], blake3_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn blake3(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, blake3_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn md5(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], md5_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn md5(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, md5_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha1(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha1_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha1(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out = hash_batches_into_string(s, &input, sha1_hash, |ca| hex_digests(ca, Sha::Sha1))?;
+        Ok(out.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha2_256(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha2_256_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha2_256(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out =
+            hash_batches_into_string(s, &input, sha2_256_hash, |ca| hex_digests(ca, Sha::Sha256))?;
+        Ok(out.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha2_512(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha2_512_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha2_512(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, sha2_512_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha2_384(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha2_384_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha2_384(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, sha2_384_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha2_224(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha2_224_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha2_224(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, sha2_224_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha3_256(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha3_256_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha3_256(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, sha3_256_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha3_512(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha3_512_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha3_512(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, sha3_512_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha3_384(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha3_384_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha3_384(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, sha3_384_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
-fn sha3_224(inputs: &[Series]) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], sha3_224_hash)?;
-    Ok(out.into_series())
+#[polars_expr(output_type_func_with_kwargs=string_output)]
+fn sha3_224(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        Ok(hash_bytes_into_string(s, &input, sha3_224_hash)?.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
+#[polars_expr(output_type_func_with_kwargs=shake_output)]
 fn sha3_shake128(inputs: &[Series], kwargs: LengthKwargs) -> PolarsResult<Series> {
-    let out = hash_bytes_into_string(&inputs[0], |value, output| {
-        sha3_shake128_hash(value, output, kwargs.length)
-    })?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out = hash_bytes_into_string(s, &input, |value, output| {
+            sha3_shake128_hash(value, output, kwargs.length)
+        })?;
+        Ok(out.into_series())
+    })
 }
 
-#[polars_expr(output_type=String)]
+#[polars_expr(output_type_func_with_kwargs=string_output)]
 fn hmac_sha256(inputs: &[Series], kwargs: HmacKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
     let keyed_mac = HmacSha256::new_from_slice(kwargs.key.as_bytes())
         .map_err(|e| PolarsError::ComputeError(format!("invalid HMAC key: {e}").into()))?;
-    let out = hash_bytes_into_string(&inputs[0], |value, output| {
-        hmac_sha256_hash(value, output, &keyed_mac)
-    })?;
-    Ok(out.into_series())
+    hash_elements(&inputs[0], None, |s, _| {
+        let out = hash_bytes_into_string(s, &input, |value, output| {
+            hmac_sha256_hash(value, output, &keyed_mac)
+        })?;
+        Ok(out.into_series())
+    })
+}
+
+/// Gives the length of each row. An integer from the caller travels as a kwarg, as a
+/// seed does, so that the output type function can check it. A column or an
+/// expression travels as the second input.
+fn length_arg(inputs: &[Series], scalar: Option<i64>, label: &str) -> PolarsResult<Int64Chunked> {
+    match scalar {
+        Some(value) => Ok(Int64Chunked::from_slice(label.into(), &[value])),
+        None => Ok(integer_arg(&inputs[1], label)?.i64()?.clone()),
+    }
 }
 
-#[polars_expr(output_type=String)]
-fn ghash_encode(inputs: &[Series]) -> PolarsResult<Series> {
+fn geohash_encode_output(fields: &[Field], kwargs: CoordsKwargs) -> PolarsResult<Field> {
+    check_coords_field(&fields[0])?;
+    if let Some(length) = kwargs.length {
+        validate_length(length)?;
+    }
+    Ok(Field::new(fields[0].name().clone(), String))
+}
+
+#[polars_expr(output_type_func_with_kwargs=geohash_encode_output)]
+fn ghash_encode(inputs: &[Series], kwargs: CoordsKwargs) -> PolarsResult<Series> {
     let ca = inputs[0].struct_()?;
-    let len = integer_arg(&inputs[1], "Length")?;
-    let len = len.i64()?;
+    let len = &length_arg(inputs, kwargs.length, "Length")?;
     let lat = float_arg(&ca.field_by_name("latitude")?, "Latitude")?;
     let long = float_arg(&ca.field_by_name("longitude")?, "Longitude")?;
     let (ca_lat, ca_long) = (lat.f64()?, long.f64()?);
+    let strict = kwargs.strict;
+    let mut rows = Rows::of(inputs[0].name());
 
     let out: StringChunked = match scalar_arg(len, "Length")? {
         Some(len) => try_binary_elementwise(ca_lat, ca_long, |lat, long| {
-            geohash_encoder(lat, long, Some(len))
+            rows.next(geohash_encoder(lat, long, Some(len), strict))
+        }),
+        None => try_ternary_elementwise(ca_lat, ca_long, len, |lat, long, len| {
+            rows.next(geohash_encoder(lat, long, len, strict))
         }),
-        None => try_ternary_elementwise(ca_lat, ca_long, len, geohash_encoder),
     }?;
     Ok(out.into_series())
 }
 
-#[polars_expr(output_type=String)]
-fn h3_encode(inputs: &[Series]) -> PolarsResult<Series> {
+fn h3_encode_output(fields: &[Field], kwargs: CoordsKwargs) -> PolarsResult<Field> {
+    check_coords_field(&fields[0])?;
+    if let Some(resolution) = kwargs.length {
+        validate_resolution(resolution)?;
+    }
+    Ok(Field::new(fields[0].name().clone(), String))
+}
+
+#[polars_expr(output_type_func_with_kwargs=h3_encode_output)]
+fn h3_encode(inputs: &[Series], kwargs: CoordsKwargs) -> PolarsResult<Series> {
     let ca = inputs[0].struct_()?;
-    let len = integer_arg(&inputs[1], "Length")?;
-    let len = len.i64()?;
+    let len = &length_arg(inputs, kwargs.length, "Length")?;
     let lat = float_arg(&ca.field_by_name("latitude")?, "Latitude")?;
     let long = float_arg(&ca.field_by_name("longitude")?, "Longitude")?;
     let (ca_lat, ca_long) = (lat.f64()?, long.f64()?);
+    let strict = kwargs.strict;
+    let mut rows = Rows::of(inputs[0].name());
 
     let out: StringChunked = match scalar_arg(len, "Length")? {
         Some(len) => try_binary_elementwise(ca_lat, ca_long, |lat, long| {
-            h3_encoder(lat, long, Some(len))
+            rows.next(h3_encoder(lat, long, Some(len), strict))
+        }),
+        None => try_ternary_elementwise(ca_lat, ca_long, len, |lat, long, len| {
+            rows.next(h3_encoder(lat, long, len, strict))
         }),
-        None => try_ternary_elementwise(ca_lat, ca_long, len, h3_encoder),
     }?;
     Ok(out.into_series())
 }
 
-pub fn geohash_decode_output(field: &[Field]) -> PolarsResult<Field> {
+fn geohash_decode_output(field: &[Field], _: StrictKwargs) -> PolarsResult<Field> {
+    check_string_field(&field[0], "Geohash")?;
     let v: Vec<Field> = vec![
         Field::new("longitude".into(), Float64),
         Field::new("latitude".into(), Float64),
@@ -358,14 +780,15 @@ pub fn geohash_decode_output(field: &[Field]) -> PolarsResult<Field> {
     Ok(Field::new(field[0].name().clone(), Struct(v)))
 }
 
-#[polars_expr(output_type_func=geohash_decode_output)]
-fn ghash_decode(inputs: &[Series]) -> PolarsResult<Series> {
+#[polars_expr(output_type_func_with_kwargs=geohash_decode_output)]
+fn ghash_decode(inputs: &[Series], kwargs: StrictKwargs) -> PolarsResult<Series> {
     let ca = inputs[0].str()?;
 
-    Ok(geohash_decoder(ca)?.into_series())
+    Ok(geohash_decoder(ca, kwargs.strict)?.into_series())
 }
 
-pub fn geohash_neighbors_output(field: &[Field]) -> PolarsResult<Field> {
+fn geohash_neighbors_output(field: &[Field], _: StrictKwargs) -> PolarsResult<Field> {
+    check_string_field(&field[0], "Geohash")?;
     let v: Vec<Field> = vec![
         Field::new("n".into(), String),
         Field::new("ne".into(), String),
@@ -379,37 +802,47 @@ pub fn geohash_neighbors_output(field: &[Field]) -> PolarsResult<Field> {
     Ok(Field::new(field[0].name().clone(), Struct(v)))
 }
 
-#[polars_expr(output_type_func=geohash_neighbors_output)]
-fn ghash_neighbors(inputs: &[Series]) -> PolarsResult<Series> {
+#[polars_expr(output_type_func_with_kwargs=geohash_neighbors_output)]
+fn ghash_neighbors(inputs: &[Series], kwargs: StrictKwargs) -> PolarsResult<Series> {
     let ca = inputs[0].str()?;
 
-    Ok(geohash_neighbors(ca)?.into_series())
+    Ok(geohash_neighbors(ca, kwargs.strict)?.into_series())
+}
+
+fn timehash_encode_output(fields: &[Field], kwargs: PrecisionKwargs) -> PolarsResult<Field> {
+    check_epoch_dtype(fields[0].dtype())?;
+    if let Some(precision) = kwargs.precision {
+        validate_precision(precision)?;
+    }
+    Ok(Field::new(fields[0].name().clone(), String))
 }
 
-#[polars_expr(output_type=String)]
-fn thash_encode(inputs: &[Series], kwargs: StrictKwargs) -> PolarsResult<Series> {
+#[polars_expr(output_type_func_with_kwargs=timehash_encode_output)]
+fn thash_encode(inputs: &[Series], kwargs: PrecisionKwargs) -> PolarsResult<Series> {
     let seconds = epoch_seconds(&inputs[0])?;
-    let precision = integer_arg(&inputs[1], "Precision")?;
-    let precision = precision.i64()?;
+    let precision = &length_arg(inputs, kwargs.precision, "Precision")?;
     let strict = kwargs.strict;
+    let mut rows = Rows::of(inputs[0].name());
 
     let out: StringChunked = match scalar_arg(precision, "Precision")? {
         Some(precision) => {
             validate_precision(precision)?;
             try_unary_elementwise(&seconds, |seconds_opt| {
-                timehash_encoder(seconds_opt, Some(precision), strict)
+                rows.next(timehash_encoder(seconds_opt, Some(precision), strict))
             })
         }
         None if seconds.len() == 1 => {
+            // The rows are those of the precision, and a bad row is a bad precision.
+            let mut rows = Rows::of(precision.name());
             let seconds = unsafe { seconds.get_unchecked(0) };
             try_unary_elementwise(precision, |precision_opt| {
-                timehash_encoder(seconds, precision_opt, strict)
+                rows.next(timehash_encoder(seconds, precision_opt, strict))
             })
             .map(|out| out.with_name(inputs[0].name().clone()))
         }
         None if seconds.len() == precision.len() => {
             try_binary_elementwise(&seconds, precision, |seconds_opt, precision_opt| {
-                timehash_encoder(seconds_opt, precision_opt, strict)
+                rows.next(timehash_encoder(seconds_opt, precision_opt, strict))
             })
         }
         None => polars_bail!(
@@ -421,21 +854,31 @@ fn thash_encode(inputs: &[Series], kwargs: StrictKwargs) -> PolarsResult<Series>
     Ok(out.into_series())
 }
 
-pub fn timehash_decode_output(field: &[Field]) -> PolarsResult<Field> {
+/// `hash_column` reads a Null column as a column of String, so the check lets it pass.
+fn check_timehash_field(field: &Field) -> PolarsResult<()> {
+    match field.dtype() {
+        DataType::Null => Ok(()),
+        _ => check_string_field(field, "Timehash"),
+    }
+}
+
+fn timehash_decode_output(field: &[Field], _: StrictKwargs) -> PolarsResult<Field> {
+    check_timehash_field(&field[0])?;
     Ok(Field::new(
         field[0].name().clone(),
         Datetime(TimeUnit::Microseconds, Some(TimeZone::UTC)),
     ))
 }
 
-#[polars_expr(output_type_func=timehash_decode_output)]
-fn thash_decode(inputs: &[Series]) -> PolarsResult<Series> {
+#[polars_expr(output_type_func_with_kwargs=timehash_decode_output)]
+fn thash_decode(inputs: &[Series], kwargs: StrictKwargs) -> PolarsResult<Series> {
     let s = hash_column(&inputs[0])?;
 
-    timehash_decoder(s.str()?)
+    timehash_decoder(s.str()?, kwargs.strict)
 }
 
-pub fn timehash_neighbors_output(field: &[Field]) -> PolarsResult<Field> {
+fn timehash_neighbors_output(field: &[Field], _: StrictKwargs) -> PolarsResult<Field> {
+    check_timehash_field(&field[0])?;
     let v: Vec<Field> = vec![
         Field::new("before".into(), String),
         Field::new("after".into(), String),
@@ -443,50 +886,251 @@ pub fn timehash_neighbors_output(field: &[Field]) -> PolarsResult<Field> {
     Ok(Field::new(field[0].name().clone(), Struct(v)))
 }
 
-#[polars_expr(output_type_func=timehash_neighbors_output)]
-fn thash_neighbors(inputs: &[Series]) -> PolarsResult<Series> {
+#[polars_expr(output_type_func_with_kwargs=timehash_neighbors_output)]
+fn thash_neighbors(inputs: &[Series], kwargs: StrictKwargs) -> PolarsResult<Series> {
     let s = hash_column(&inputs[0])?;
 
-    Ok(timehash_neighbors(s.str()?)?.into_series())
+    Ok(timehash_neighbors(s.str()?, kwargs.strict)?.into_series())
 }
 
-#[polars_expr(output_type=UInt32)]
+#[polars_expr(output_type_func_with_kwargs=hash_32_output)]
 fn murmur32(inputs: &[Series], kwargs: SeedKwargs32bit) -> PolarsResult<Series> {
-    let seed = kwargs.seed;
-    let out: UInt32Chunked = hash_bytes(&inputs[0], |v| murmurhash3_32(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        let out: UInt32Chunked = hash_bytes_seeded::<_, UInt32Type, _, _>(
+            s,
+            &input,
+            seeds,
+            kwargs.seed,
+            murmurhash3_32,
+        )?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
+}
+
+#[polars_expr(output_type_func_with_kwargs=hash_32_output)]
+fn kafka_murmur2(inputs: &[Series], kwargs: SignedKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: UInt32Chunked = hash_bytes(s, &input, murmurhash2_kafka)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
+}
+
+/// Kafka keeps the number of partitions of a topic in a Java `int`.
+fn check_partitions(partitions: i64) -> PolarsResult<i32> {
+    match i32::try_from(partitions) {
+        Ok(partitions) if partitions >= 1 => Ok(partitions),
+        _ => polars_bail!(
+            InvalidOperation:
+            "expected a number of partitions between 1 and {}, got {}", i32::MAX, partitions
+        ),
+    }
+}
+
+/// Kafka does not hash a null key, and gives the record a partition of its own choice.
+/// Therefore a null key has no partition here, and `null_as` may not give it bytes.
+fn check_null_keys(input: &InputKwargs) -> PolarsResult<()> {
+    polars_ensure!(
+        input.propagates_nulls(),
+        InvalidOperation: "Kafka does not hash a null key, so `null_as` must be \"propagate\""
+    );
+    Ok(())
+}
+
+fn kafka_partition_output(fields: &[Field], kwargs: KafkaKwargs) -> PolarsResult<Field> {
+    check_partitions(kwargs.partitions)?;
+    check_null_keys(&kwargs.input)?;
+    check_hasher_fields(fields, &kwargs.input)?;
+    hashed_output(fields, DataType::Int32)
+}
+
+#[polars_expr(output_type_func_with_kwargs=kafka_partition_output)]
+fn kafka_partition(inputs: &[Series], kwargs: KafkaKwargs) -> PolarsResult<Series> {
+    let partitions = check_partitions(kwargs.partitions)?;
+    check_null_keys(&kwargs.input)?;
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: Int32Chunked = hash_bytes(s, &input, |v| kafka_default_partition(v, partitions))?;
+        Ok(out.into_series())
+    })
+}
+
+fn clickhouse_output(fields: &[Field]) -> PolarsResult<Field> {
+    check_clickhouse_dtype(fields[0].dtype())?;
+    Ok(Field::new(fields[0].name().clone(), DataType::UInt64))
+}
+
+/// A Struct is the arguments of one call, and a List is an `Array` argument.
+/// Therefore the input is not split into its elements, as it is for another hasher.
+fn clickhouse_hash(s: &Series, f: Function) -> PolarsResult<Series> {
+    let hashes = column_hashes(s, f)?;
+    Ok(UInt64Chunked::from_iter_options(s.name().clone(), hashes.into_iter()).into_series())
+}
+
+#[polars_expr(output_type_func=clickhouse_output)]
+fn clickhouse_city_hash64(inputs: &[Series]) -> PolarsResult<Series> {
+    clickhouse_hash(&inputs[0], Function::CityHash64)
+}
+
+#[polars_expr(output_type_func=clickhouse_output)]
+fn clickhouse_sip_hash64(inputs: &[Series]) -> PolarsResult<Series> {
+    clickhouse_hash(&inputs[0], Function::SipHash64)
+}
+
+#[polars_expr(output_type_func=clickhouse_output)]
+fn clickhouse_murmur_hash3_64(inputs: &[Series]) -> PolarsResult<Series> {
+    clickhouse_hash(&inputs[0], Function::MurmurHash3_64)
+}
+
+#[polars_expr(output_type_func=clickhouse_output)]
+fn clickhouse_half_md5(inputs: &[Series]) -> PolarsResult<Series> {
+    clickhouse_hash(&inputs[0], Function::HalfMd5)
+}
+
+fn jvm_hash_code_output(fields: &[Field]) -> PolarsResult<Field> {
+    check_hash_code_dtype(fields[0].dtype())?;
+    hashed_output(fields, DataType::Int32)
+}
+
+/// A Struct is the arguments of `Objects.hash`, and therefore one hash for each row.
+#[polars_expr(output_type_func=jvm_hash_code_output)]
+fn jvm_hash_code(inputs: &[Series]) -> PolarsResult<Series> {
+    hash_elements(&inputs[0], None, |s, _| Ok(hash_codes(s)?.into_series()))
+}
+
+/// A Guava seed is a Java `int`.
+fn check_guava_seed(seed: i64) -> PolarsResult<i32> {
+    i32::try_from(seed).map_err(|_| {
+        polars_err!(
+            InvalidOperation:
+            "expected a seed between {} and {}, got {}", i32::MIN, i32::MAX, seed
+        )
+    })
+}
+
+fn guava_output(fields: &[Field], kwargs: &GuavaKwargs, dtype: DataType) -> PolarsResult<Field> {
+    check_guava_seed(kwargs.seed)?;
+    check_funnel_dtype(fields[0].dtype())?;
+    hashed_output(fields, dtype)
+}
+
+fn guava_murmur3_32_output(fields: &[Field], kwargs: GuavaKwargs) -> PolarsResult<Field> {
+    guava_output(fields, &kwargs, DataType::Int32)
+}
+
+fn guava_murmur3_128_output(fields: &[Field], kwargs: GuavaKwargs) -> PolarsResult<Field> {
+    guava_output(fields, &kwargs, DataType::Int64)
+}
+
+#[polars_expr(output_type_func_with_kwargs=guava_murmur3_32_output)]
+fn guava_murmur3_32(inputs: &[Series], kwargs: GuavaKwargs) -> PolarsResult<Series> {
+    let seed = check_guava_seed(kwargs.seed)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let bytes = funnel_bytes(s, kwargs.input_encoding, kwargs.on_decode_error)?;
+        let out: Int32Chunked =
+            unary_elementwise_values(&bytes, |v: &[u8]| murmurhash3_32_guava(v, seed));
+        Ok(out.into_series())
+    })
+}
+
+#[polars_expr(output_type_func_with_kwargs=guava_murmur3_128_output)]
+fn guava_murmur3_128(inputs: &[Series], kwargs: GuavaKwargs) -> PolarsResult<Series> {
+    let seed = check_guava_seed(kwargs.seed)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let bytes = funnel_bytes(s, kwargs.input_encoding, kwargs.on_decode_error)?;
+        let out: Int64Chunked =
+            unary_elementwise_values(&bytes, |v: &[u8]| murmurhash3_128_guava(v, seed));
+        Ok(out.into_series())
+    })
+}
+
+fn redis_slot_output(fields: &[Field], kwargs: InputKwargs) -> PolarsResult<Field> {
+    kwargs.check_fields(fields)?;
+    hashed_output(fields, DataType::UInt16)
+}
+
+#[polars_expr(output_type_func_with_kwargs=redis_slot_output)]
+fn redis_slot(inputs: &[Series], kwargs: InputKwargs) -> PolarsResult<Series> {
+    let input = kwargs.with_inputs(inputs)?;
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: UInt16Chunked = hash_bytes(s, &input, redis_cluster_slot)?;
+        Ok(out.into_series())
+    })
+}
+
+fn cassandra_token_output(fields: &[Field]) -> PolarsResult<Field> {
+    check_key_dtype(fields[0].dtype())?;
+    hashed_output(fields, DataType::Int64)
+}
+
+#[polars_expr(output_type_func=cassandra_token_output)]
+fn cassandra_token(inputs: &[Series]) -> PolarsResult<Series> {
+    hash_elements(&inputs[0], None, |s, _| {
+        let out: Int64Chunked = unary_elementwise_values(&key_bytes(s)?, murmur3_partitioner_token);
+        Ok(out.into_series())
+    })
 }
 
 #[polars_expr(output_type_func_with_kwargs=hash_128_output)]
 fn murmur128(inputs: &[Series], kwargs: Seed32AndBinaryKwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
     let seed = kwargs.seed;
-    if kwargs.return_binary {
-        let out = hash_bytes_into_binary(&inputs[0], |v| murmurhash3_128(v, seed).to_le_bytes())?;
-        return Ok(out.into_series());
-    }
-    let out: UInt128Chunked = hash_bytes(&inputs[0], |v| murmurhash3_128(v, seed))?;
-    Ok(out.into_series())
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        if kwargs.return_binary {
+            let out = hash_bytes_seeded_into_binary::<_, UInt32Type, _>(
+                s,
+                &input,
+                seeds,
+                seed,
+                |v, seed| murmurhash3_128(v, seed).to_le_bytes(),
+            )?;
+            return Ok(out.into_series());
+        }
+        let out: UInt128Chunked =
+            hash_bytes_seeded::<_, UInt32Type, _, _>(s, &input, seeds, seed, murmurhash3_128)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
-#[polars_expr(output_type=UInt32)]
+#[polars_expr(output_type_func_with_kwargs=hash_32_output)]
 fn xxhash32(inputs: &[Series], kwargs: SeedKwargs32bit) -> PolarsResult<Series> {
-    let seed = kwargs.seed;
-    let out: UInt32Chunked = hash_bytes(&inputs[0], |v| xxhash_32(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        let out: UInt32Chunked =
+            hash_bytes_seeded::<_, UInt32Type, _, _>(s, &input, seeds, kwargs.seed, xxhash_32)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
-#[polars_expr(output_type=UInt64)]
+#[polars_expr(output_type_func_with_kwargs=hash_64_output)]
 fn xxhash64(inputs: &[Series], kwargs: SeedKwargs64bit) -> PolarsResult<Series> {
-    let seed = kwargs.seed as u64;
-    let out: UInt64Chunked = hash_bytes(&inputs[0], |v| xxhash_64(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        let out: UInt64Chunked = hash_bytes_seeded::<_, UInt64Type, _, _>(
+            s,
+            &input,
+            seeds,
+            kwargs.seed as u64,
+            xxhash_64,
+        )?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
-#[polars_expr(output_type=UInt64)]
+#[polars_expr(output_type_func_with_kwargs=hash_64_output)]
 fn xxh3_64(inputs: &[Series], kwargs: SeedKwargs64bit) -> PolarsResult<Series> {
-    let seed = kwargs.seed as u64;
-    let out: UInt64Chunked = hash_bytes(&inputs[0], |v| xxhash3_64(v, seed))?;
-    Ok(out.into_series())
+    let input = kwargs.input.with_inputs(inputs)?;
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        let out: UInt64Chunked = hash_bytes_seeded::<_, UInt64Type, _, _>(
+            s,
+            &input,
+            seeds,
+            kwargs.seed as u64,
+            xxhash3_64,
+        )?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
 }
 
 /// Big-endian bytes are the digest that `XXH128_canonicalFromHash` writes. Little-endian
@@ -494,49 +1138,207 @@ fn xxh3_64(inputs: &[Series], kwargs: SeedKwargs64bit) -> PolarsResult<Series> {
 /// output became `UInt128`, and therefore what a reader from that time expects.
 #[polars_expr(output_type_func_with_kwargs=hash_128_output)]
 fn xxh3_128(inputs: &[Series], kwargs: Xxh3Kwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
     let seed = kwargs.seed as u64;
-    if kwargs.return_binary {
-        let out = if kwargs.big_endian {
-            hash_bytes_into_binary(&inputs[0], |v| xxhash3_128(v, seed).to_be_bytes())?
-        } else {
-            hash_bytes_into_binary(&inputs[0], |v| xxhash3_128(v, seed).to_le_bytes())?
-        };
-        return Ok(out.into_series());
+    hash_elements(&inputs[0], inputs.get(1), |s, seeds| {
+        if kwargs.return_binary {
+            let out = if kwargs.big_endian {
+                hash_bytes_seeded_into_binary::<_, UInt64Type, _>(
+                    s,
+                    &input,
+                    seeds,
+                    seed,
+                    |v, seed| xxhash3_128(v, seed).to_be_bytes(),
+                )?
+            } else {
+                hash_bytes_seeded_into_binary::<_, UInt64Type, _>(
+                    s,
+                    &input,
+                    seeds,
+                    seed,
+                    |v, seed| xxhash3_128(v, seed).to_le_bytes(),
+                )?
+            };
+            return Ok(out.into_series());
+        }
+        let out: UInt128Chunked =
+            hash_bytes_seeded::<_, UInt64Type, _, _>(s, &input, seeds, seed, xxhash3_128)?;
+        with_sign(out.into_series(), kwargs.signed)
+    })
+}
+
+/// PostgreSQL keeps the modulus of a hash partition in an `int4`.
+fn check_modulus(modulus: i64) -> PolarsResult<i32> {
+    match i32::try_from(modulus) {
+        Ok(modulus) if modulus >= 1 => Ok(modulus),
+        _ => polars_bail!(
+            InvalidOperation:
+            "expected a modulus between 1 and {}, got {}", i32::MAX, modulus
+        ),
+    }
+}
+
+fn postgres_partition_output(fields: &[Field], kwargs: PostgresKwargs) -> PolarsResult<Field> {
+    check_modulus(kwargs.modulus)?;
+    // `plh.postgres_partition` gives the key columns as one Struct.
+    if let DataType::Struct(columns) = fields[0].dtype() {
+        columns
+            .iter()
+            .try_for_each(|column| check_partition_key_dtype(column.dtype()))?;
+    }
+    Ok(Field::new(fields[0].name().clone(), DataType::Int32))
+}
+
+#[polars_expr(output_type_func_with_kwargs=postgres_partition_output)]
+fn postgres_partition(inputs: &[Series], kwargs: PostgresKwargs) -> PolarsResult<Series> {
+    let modulus = check_modulus(kwargs.modulus)?;
+    Ok(partition_remainders(&inputs[0], modulus)?.into_series())
+}
+
+/// Hive and Iceberg keep the number of buckets in a Java `int`.
+fn check_buckets(buckets: i64) -> PolarsResult<i32> {
+    match i32::try_from(buckets) {
+        Ok(buckets) if buckets >= 1 => Ok(buckets),
+        _ => polars_bail!(
+            InvalidOperation:
+            "expected a number of buckets between 1 and {}, got {}", i32::MAX, buckets
+        ),
     }
-    let out: UInt128Chunked = hash_bytes(&inputs[0], |v| xxhash3_128(v, seed))?;
-    Ok(out.into_series())
 }
 
-#[polars_expr(output_type=Binary)]
+fn hive_bucket_output(fields: &[Field], kwargs: BucketKwargs) -> PolarsResult<Field> {
+    check_buckets(kwargs.buckets)?;
+    // `plh.hive_bucket` gives the bucket columns as one Struct.
+    check_hive_dtype(fields[0].dtype())?;
+    Ok(Field::new(fields[0].name().clone(), DataType::Int32))
+}
+
+#[polars_expr(output_type_func_with_kwargs=hive_bucket_output)]
+fn hive_bucket(inputs: &[Series], kwargs: BucketKwargs) -> PolarsResult<Series> {
+    let buckets = check_buckets(kwargs.buckets)?;
+    Ok(hive_buckets(&inputs[0], buckets)?.into_series())
+}
+
+fn iceberg_bucket_output(fields: &[Field], kwargs: BucketKwargs) -> PolarsResult<Field> {
+    check_buckets(kwargs.buckets)?;
+    check_bucket_dtype(fields[0].dtype())?;
+    Ok(Field::new(fields[0].name().clone(), DataType::Int32))
+}
+
+#[polars_expr(output_type_func_with_kwargs=iceberg_bucket_output)]
+fn iceberg_bucket(inputs: &[Series], kwargs: BucketKwargs) -> PolarsResult<Series> {
+    let buckets = check_buckets(kwargs.buckets)?;
+    Ok(iceberg::buckets(&inputs[0], buckets)?.into_series())
+}
+
+/// Iceberg keeps the width of a `truncate` transform in a Java `int`.
+fn check_width(width: i64) -> PolarsResult<i32> {
+    match i32::try_from(width) {
+        Ok(width) if width >= 1 => Ok(width),
+        _ => polars_bail!(
+            InvalidOperation:
+            "expected a width between 1 and {}, got {}", i32::MAX, width
+        ),
+    }
+}
+
+fn iceberg_truncate_output(fields: &[Field], kwargs: TruncateKwargs) -> PolarsResult<Field> {
+    check_width(kwargs.width)?;
+    let dtype = truncated_dtype(fields[0].dtype())?;
+    Ok(Field::new(fields[0].name().clone(), dtype))
+}
+
+#[polars_expr(output_type_func_with_kwargs=iceberg_truncate_output)]
+fn iceberg_truncate(inputs: &[Series], kwargs: TruncateKwargs) -> PolarsResult<Series> {
+    let width = check_width(kwargs.width)?;
+    iceberg::truncate(&inputs[0], width)
+}
+
+fn spark_output(fields: &[Field], dtype: DataType) -> PolarsResult<Field> {
+    // `plh.spark_hash` gives the columns as one Struct.
+    check_spark_dtype(fields[0].dtype())?;
+    Ok(Field::new(fields[0].name().clone(), dtype))
+}
+
+fn spark_hash_output(fields: &[Field]) -> PolarsResult<Field> {
+    spark_output(fields, DataType::Int32)
+}
+
+fn spark_xxhash64_output(fields: &[Field]) -> PolarsResult<Field> {
+    spark_output(fields, DataType::Int64)
+}
+
+#[polars_expr(output_type_func=spark_hash_output)]
+fn spark_hash(inputs: &[Series]) -> PolarsResult<Series> {
+    let hashes = row_hashes(&inputs[0], spark::Function::Murmur3)?;
+    let out = hashes.into_iter().map(|h| h as u32 as i32).collect();
+    Ok(Int32Chunked::from_vec(inputs[0].name().clone(), out).into_series())
+}
+
+#[polars_expr(output_type_func=spark_xxhash64_output)]
+fn spark_xxhash64(inputs: &[Series]) -> PolarsResult<Series> {
+    let hashes = row_hashes(&inputs[0], spark::Function::XxHash64)?;
+    let out = hashes.into_iter().map(|h| h as i64).collect();
+    Ok(Int64Chunked::from_vec(inputs[0].name().clone(), out).into_series())
+}
+
+fn encode_rows_output(fields: &[Field], kwargs: VersionKwargs) -> PolarsResult<Field> {
+    check_version(kwargs.version)?;
+    fields.iter().try_for_each(|f| check_row_dtype(f.dtype()))?;
+    Ok(Field::new(fields[0].name().clone(), DataType::Binary))
+}
+
+#[polars_expr(output_type_func_with_kwargs=encode_rows_output)]
 fn encode_rows(inputs: &[Series], kwargs: VersionKwargs) -> PolarsResult<Series> {
     Ok(crate::row_encode::encode_rows(inputs, kwargs.version)?.into_series())
 }
 
-#[polars_expr(output_type=String)]
-fn uuid5(inputs: &[Series]) -> PolarsResult<Series> {
+fn uuid5_output(fields: &[Field], kwargs: Uuid5Kwargs) -> PolarsResult<Field> {
+    check_string_field(&fields[1], "Namespace")?;
+    string_output(fields, kwargs.input)
+}
+
+#[polars_expr(output_type_func_with_kwargs=uuid5_output)]
+fn uuid5(inputs: &[Series], kwargs: Uuid5Kwargs) -> PolarsResult<Series> {
+    let input = kwargs.input.with_inputs(inputs)?;
     let namespace_str = inputs[1].str()?;
     let ns_value = namespace_str
         .get(0)
         .ok_or_else(|| PolarsError::ComputeError("Namespace must be provided".into()))?;
 
     let namespace = match ns_value.to_lowercase().as_str() {
-        "dns" => uuid::Uuid::NAMESPACE_DNS,
-        "url" => uuid::Uuid::NAMESPACE_URL,
-        "oid" => uuid::Uuid::NAMESPACE_OID,
-        "x500" => uuid::Uuid::NAMESPACE_X500,
+        "dns" => Ok(uuid::Uuid::NAMESPACE_DNS),
+        "url" => Ok(uuid::Uuid::NAMESPACE_URL),
+        "oid" => Ok(uuid::Uuid::NAMESPACE_OID),
+        "x500" => Ok(uuid::Uuid::NAMESPACE_X500),
         _ => uuid::Uuid::parse_str(ns_value).map_err(|e| {
             PolarsError::ComputeError(format!("Invalid namespace '{}': {}", ns_value, e).into())
-        })?,
+        }),
+    };
+    // The namespace is one value for the whole column, so an invalid one makes each
+    // row invalid.
+    let Some(namespace) = or_null(namespace, kwargs.strict)? else {
+        let dtype = hashed_dtype(inputs[0].dtype(), DataType::String);
+        return Ok(Series::full_null(
+            inputs[0].name().clone(),
+            inputs[0].len(),
+            &dtype,
+        ));
     };
 
-    let out = hash_bytes_into_string(&inputs[0], |value, output| {
-        output.push_str(
-            &uuid::Uuid::new_v5(&namespace, value)
-                .hyphenated()
-                .to_string(),
-        )
-    })?;
-    Ok(out.into_series())
+    hash_elements(&inputs[0], None, |s, _| {
+        let out = hash_bytes_into_string(s, &input, |value, output| {
+            // A UUID hashes one slice, so the parts go into one buffer.
+            value.joined(|value| {
+                output.push_str(
+                    &uuid::Uuid::new_v5(&namespace, value)
+                        .hyphenated()
+                        .to_string(),
+                )
+            })
+        })?;
+        Ok(out.into_series())
+    })
 }
 
 /// A null second value falls back to `default`, which is `""` when the caller gave none.
@@ -582,7 +1384,20 @@ fn uuid5_concat_impl(
     Ok(out.with_name(col1.name().clone()))
 }
 
-#[polars_expr(output_type=String)]
+fn uuid5_concat_output(fields: &[Field]) -> PolarsResult<Field> {
+    check_string_field(&fields[0], "First")?;
+    check_string_field(&fields[1], "Second")?;
+    Ok(Field::new(fields[0].name().clone(), String))
+}
+
+/// The second column is cast to String, so any data type that casts passes here.
+fn uuid5_concat_default_output(fields: &[Field]) -> PolarsResult<Field> {
+    check_string_field(&fields[0], "First")?;
+    check_string_field(&fields[2], "Default")?;
+    Ok(Field::new(fields[0].name().clone(), String))
+}
+
+#[polars_expr(output_type_func=uuid5_concat_output)]
 fn uuid5_concat(inputs: &[Series]) -> PolarsResult<Series> {
     let col1 = inputs[0].str()?;
     let col2 = inputs[1].str()?;
@@ -590,7 +1405,7 @@ fn uuid5_concat(inputs: &[Series]) -> PolarsResult<Series> {
     Ok(uuid5_concat_impl(col1, col2, "")?.into_series())
 }
 
-#[polars_expr(output_type=String)]
+#[polars_expr(output_type_func=uuid5_concat_default_output)]
 fn uuid5_concat_default(inputs: &[Series]) -> PolarsResult<Series> {
     let col1 = inputs[0].str()?;
     let col2_casted = inputs[1].cast(&DataType::String)?;
@@ -1,18 +1,31 @@
-use crate::shared::string_struct;
-use geohash::{decode, encode, neighbors, Coord};
+use crate::shared::{at_row, or_null, string_struct};
+use geohash::{decode, encode, neighbors, Coord, GeohashError};
 use polars::prelude::*;
 
+/// The `geohash` crate encodes 1 to 12 characters. The expression checks a scalar
+/// length when polars builds the plan, and the crate checks the length of each row.
+pub fn validate_length(len: i64) -> PolarsResult<()> {
+    if !(1..=12).contains(&len) {
+        polars_bail!(InvalidOperation: "expected length between 1 and 12, got {}", len)
+    }
+    Ok(())
+}
+
+/// `strict` softens a coordinate out of range only. The length is an argument and not
+/// data, so it stays strict, as the precision of `timehash_encoder` does.
 pub fn geohash_encoder(
     lat: Option<f64>,
     long: Option<f64>,
     len: Option<i64>,
+    strict: bool,
 ) -> PolarsResult<Option<String>> {
     match (lat, long) {
         (Some(lat), Some(long)) => match len {
-            Some(len) => Ok(Some(
-                encode(Coord { x: long, y: lat }, len as usize)
-                    .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?,
-            )),
+            Some(len) => match encode(Coord { x: long, y: lat }, len as usize) {
+                Ok(hash) => Ok(Some(hash)),
+                Err(GeohashError::InvalidCoordinateRange(_)) if !strict => Ok(None),
+                Err(e) => Err(PolarsError::ComputeError(e.to_string().into())),
+            },
             _ => Err(PolarsError::ComputeError(
                 "Length may not be null".to_string().into(),
             )),
@@ -21,17 +34,28 @@ pub fn geohash_encoder(
     }
 }
 
-pub fn geohash_decoder(ca: &StringChunked) -> PolarsResult<StructChunked> {
+pub fn geohash_decoder(ca: &StringChunked, strict: bool) -> PolarsResult<StructChunked> {
     let mut longitude: PrimitiveChunkedBuilder<Float64Type> =
         PrimitiveChunkedBuilder::new("longitude".into(), ca.len());
     let mut latitude: PrimitiveChunkedBuilder<Float64Type> =
         PrimitiveChunkedBuilder::new("latitude".into(), ca.len());
 
-    for value in ca.iter() {
-        match value {
-            Some(value) => {
-                let (cords, _, _) =
-                    decode(value).map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    for (row, value) in ca.iter().enumerate() {
+        let decoded = value
+            .map(|value| {
+                let decoded = decode(value).map_err(|e| {
+                    at_row(
+                        PolarsError::ComputeError(e.to_string().into()),
+                        row,
+                        ca.name(),
+                    )
+                });
+                or_null(decoded, strict)
+            })
+            .transpose()?
+            .flatten();
+        match decoded {
+            Some((cords, _, _)) => {
                 let (x_value, y_value) = cords.x_y();
                 longitude.append_value(x_value);
                 latitude.append_value(y_value);
@@ -47,8 +71,9 @@ pub fn geohash_decoder(ca: &StringChunked) -> PolarsResult<StructChunked> {
     StructChunked::from_series(ca.name().clone(), ca.len(), [ser_long, ser_lat].iter())
 }
 
-pub fn geohash_neighbors(ca: &StringChunked) -> PolarsResult<StructChunked> {
-    string_struct(ca, ["n", "ne", "e", "se", "s", "sw", "w", "nw"], |value| {
+pub fn geohash_neighbors(ca: &StringChunked, strict: bool) -> PolarsResult<StructChunked> {
+    let names = ["n", "ne", "e", "se", "s", "sw", "w", "nw"];
+    string_struct(ca, names, strict, |value| {
         let n = neighbors(value).map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
         Ok([
             Some(n.n),
@@ -1,3 +1,4 @@
+use crate::shared::or_null;
 use h3o::{LatLng, Resolution};
 use polars::prelude::*;
 
@@ -24,26 +25,39 @@ fn get_resolution(resolution: i64) -> PolarsResult<Resolution> {
     }
 }
 
+/// Checks a scalar resolution when polars builds the plan.
+pub fn validate_resolution(resolution: i64) -> PolarsResult<()> {
+    get_resolution(resolution).map(|_| ())
+}
+
+fn lat_lng(lat: f64, long: f64) -> PolarsResult<LatLng> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&long) {
+        polars_bail!(
+            ComputeError:
+            "invalid coordinate range: latitude {}, longitude {}", lat, long
+        )
+    }
+    LatLng::new(lat, long).map_err(|e| {
+        PolarsError::ComputeError(
+            format!("invalid coordinate: latitude {lat}, longitude {long}: {e}").into(),
+        )
+    })
+}
+
+/// `strict` softens an invalid coordinate only. The resolution is an argument and not
+/// data, so it stays strict, as the precision of a timehash does.
 pub fn h3_encoder(
     lat: Option<f64>,
     long: Option<f64>,
     len: Option<i64>,
+    strict: bool,
 ) -> PolarsResult<Option<String>> {
     match (lat, long) {
         (Some(lat), Some(long)) => match len {
             Some(len) => {
-                if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&long) {
-                    polars_bail!(
-                        ComputeError:
-                        "invalid coordinate range: latitude {}, longitude {}", lat, long
-                    )
-                }
-                let coord = LatLng::new(lat, long).map_err(|e| {
-                    PolarsError::ComputeError(
-                        format!("invalid coordinate: latitude {lat}, longitude {long}: {e}").into(),
-                    )
-                })?;
-                Ok(Some(coord.to_cell(get_resolution(len)?).to_string()))
+                let resolution = get_resolution(len)?;
+                let coord = or_null(lat_lng(lat, long), strict)?;
+                Ok(coord.map(|coord| coord.to_cell(resolution).to_string()))
             }
             _ => Err(PolarsError::ComputeError(
                 "Length may not be null".to_string().into(),
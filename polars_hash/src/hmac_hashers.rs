@@ -1,11 +1,21 @@
+use crate::affix::Affixed;
+use crate::registry::{Algorithm, Hasher, Seed};
+use crate::shared::write_hex;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
-use std::fmt::Write;
 
 pub type HmacSha256 = Hmac<Sha256>;
 
-pub fn hmac_sha256_hash(value: &[u8], output: &mut String, keyed_mac: &HmacSha256) {
+pub fn hmac_sha256_hash(value: Affixed, output: &mut String, keyed_mac: &HmacSha256) {
     let mut mac = keyed_mac.clone();
-    mac.update(value);
-    write!(output, "{}", hex::encode(mac.finalize().into_bytes())).unwrap()
+    for part in value.parts() {
+        mac.update(part);
+    }
+    write_hex(&mac.finalize().into_bytes(), output)
 }
+
+pub static ALGORITHMS: &[Algorithm] = &[Algorithm {
+    name: "hmac_sha256",
+    seed: Seed::None,
+    hasher: Hasher::Mac(hmac_sha256_hash),
+}];
@@ -0,0 +1,166 @@
+//! The `bucket[N]` and `truncate[W]` partition transforms of Apache Iceberg.
+//!
+//! Iceberg hashes the bytes of the single-value serialization of a value with
+//! MurmurHash3 x86 32 and the seed 0, as Guava's `murmur3_32_fixed` does. Therefore
+//! the bucket reuses [`murmurhash3_32_guava`]. An `int` is promoted to a `long` before
+//! the hash, so that a column can change from `int` to `long` and keep its buckets.
+//! For the same reason a `date` hashes its days as a `long`.
+
+use crate::murmurhash_hashers::murmurhash3_32_guava;
+use crate::row_encode::to_micros;
+use crate::spark::big_integer_bytes;
+use polars::chunked_array::ops::arity::unary_elementwise_values;
+use polars::prelude::*;
+
+/// Checks that [`buckets`] reads a column of `dtype`, when polars builds the plan.
+pub fn check_bucket_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::Unknown(_)
+        | DataType::Null
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Decimal(_, _)
+        | DataType::Date
+        | DataType::Time
+        | DataType::Datetime(_, _)
+        | DataType::String
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _)
+        | DataType::Binary => Ok(()),
+        dtype => Err(no_bucket_type(dtype)),
+    }
+}
+
+/// Checks that [`truncate`] reads a column of `dtype`, and gives the type of its
+/// output. Iceberg computes an `int` in 32 bits. Therefore an Int8 and an Int16 give
+/// an Int32, because the truncated value can be out of their range.
+pub fn truncated_dtype(dtype: &DataType) -> PolarsResult<DataType> {
+    let out = match dtype {
+        DataType::Unknown(_) | DataType::Null => DataType::Null,
+        DataType::Int8 | DataType::Int16 | DataType::Int32 => DataType::Int32,
+        DataType::Int64 => DataType::Int64,
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => DataType::String,
+        DataType::Decimal(_, _) | DataType::String | DataType::Binary => dtype.clone(),
+        dtype => return Err(no_truncate_type(dtype)),
+    };
+    Ok(out)
+}
+
+fn no_bucket_type(dtype: &DataType) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected a signed integer, `Decimal`, `Date`, `Time`, `Datetime`, `String` or \
+        `Binary` input for an Iceberg bucket, got `{}`",
+        dtype
+    )
+}
+
+fn no_truncate_type(dtype: &DataType) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected a signed integer, `Decimal`, `String` or `Binary` input for an Iceberg \
+        truncate, got `{}`",
+        dtype
+    )
+}
+
+/// The hash of a `long`, which is its 8 bytes, least significant byte first.
+fn hash_long(value: i64) -> i32 {
+    murmurhash3_32_guava(&value.to_le_bytes(), 0)
+}
+
+/// The Iceberg hash of each value of `s`. A null stays null.
+fn hashes(s: &Series) -> PolarsResult<Int32Chunked> {
+    let long = |s: &Series| -> PolarsResult<Int32Chunked> {
+        let s = s.cast(&DataType::Int64)?;
+        Ok(unary_elementwise_values(s.i64()?, hash_long))
+    };
+    let bytes = |ca: &BinaryChunked| unary_elementwise_values(ca, |v| murmurhash3_32_guava(v, 0));
+    let out = match s.dtype() {
+        DataType::Null => Int32Chunked::full_null(s.name().clone(), s.len()),
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => long(s)?,
+        // A `date` counts the days from 1970-01-01, as a Polars Date does.
+        DataType::Date => long(&s.to_physical_repr())?,
+        // A `time` counts microseconds from midnight. A Polars Time counts nanoseconds.
+        DataType::Time => unary_elementwise_values(s.to_physical_repr().i64()?, |v| {
+            hash_long(v.div_euclid(1_000))
+        }),
+        // A `timestamp` and a `timestamptz` count microseconds from 1970-01-01.
+        DataType::Datetime(unit, _) => {
+            let micros = to_micros(s.to_physical_repr().i64()?, unit);
+            unary_elementwise_values(&micros, hash_long)
+        }
+        // A `decimal` hashes its unscaled value as `BigInteger.toByteArray`.
+        DataType::Decimal(_, _) => unary_elementwise_values(s.to_physical_repr().i128()?, |v| {
+            murmurhash3_32_guava(&big_integer_bytes(v), 0)
+        }),
+        DataType::String => bytes(&s.str()?.as_binary()),
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            bytes(&s.cast(&DataType::String)?.str()?.as_binary())
+        }
+        // A `uuid` is its 16 bytes, most significant byte first, and a `fixed` is its
+        // bytes. Therefore Binary holds the two types, and `binary`.
+        DataType::Binary => bytes(s.binary()?),
+        dtype => return Err(no_bucket_type(dtype)),
+    };
+    Ok(out.with_name(s.name().clone()))
+}
+
+/// Gives the `bucket[buckets]` of each value of `s`. This is the hash without its sign
+/// bit, modulo the number of buckets. A null value gives null.
+pub fn buckets(s: &Series, buckets: i32) -> PolarsResult<Int32Chunked> {
+    Ok(hashes(s)?.apply_values(|hash| (hash & i32::MAX) % buckets))
+}
+
+/// Gives the `truncate[width]` of each value of `s`. A number is rounded down to a
+/// multiple of `width`, a string keeps its first `width` code points, and Binary keeps
+/// its first `width` bytes. A null value gives null.
+pub fn truncate(s: &Series, width: i32) -> PolarsResult<Series> {
+    let name = s.name().clone();
+    let out = match s.dtype() {
+        DataType::Null => s.clone(),
+        // Iceberg computes an `int` with the overflow of a Java `int`.
+        DataType::Int8 | DataType::Int16 | DataType::Int32 => {
+            let s = s.cast(&DataType::Int32)?;
+            let out: Int32Chunked =
+                unary_elementwise_values(s.i32()?, |v| v.wrapping_sub(v.rem_euclid(width)));
+            out.into_series()
+        }
+        DataType::Int64 => {
+            let width = width as i64;
+            let out: Int64Chunked =
+                unary_elementwise_values(s.i64()?, |v| v.wrapping_sub(v.rem_euclid(width)));
+            out.into_series()
+        }
+        // The unscaled value is truncated, and the scale stays. A value that needs one
+        // more digit than the precision is an error, as polars gives for a cast.
+        DataType::Decimal(precision, scale) => {
+            let width = width as i128;
+            let out: Int128Chunked =
+                unary_elementwise_values(s.to_physical_repr().i128()?, |v| v - v.rem_euclid(width));
+            out.into_decimal(*precision, *scale)?.into_series()
+        }
+        DataType::String => strings(s.str()?, width).into_series(),
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            strings(s.cast(&DataType::String)?.str()?, width).into_series()
+        }
+        DataType::Binary => {
+            let out: BinaryChunked =
+                unary_elementwise_values(s.binary()?, |v| &v[..v.len().min(width as usize)]);
+            out.into_series()
+        }
+        dtype => return Err(no_truncate_type(dtype)),
+    };
+    Ok(out.with_name(name))
+}
+
+/// Keeps the first `width` code points of each string, as Iceberg counts a string in
+/// code points and not in bytes.
+fn strings(ca: &StringChunked, width: i32) -> StringChunked {
+    unary_elementwise_values(ca, |v: &str| match v.char_indices().nth(width as usize) {
+        Some((end, _)) => &v[..end],
+        None => v,
+    })
+}
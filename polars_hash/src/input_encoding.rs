@@ -0,0 +1,143 @@
+//! Changes the text of a String column into the bytes of another encoding, before a
+//! hasher reads it.
+//!
+//! A hasher reads the UTF-8 bytes of a String. Other systems hash other bytes: .NET,
+//! Windows and Java hash UTF-16LE, and an old system hashes Latin-1. Some feeds also
+//! give the bytes as hex or base64 text. The hash of the same bytes is the same hash
+//! in each system, and therefore these encodings give the bytes that the other system
+//! hashed.
+
+use base64::Engine;
+use polars::prelude::*;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+/// The encoding of the bytes that a hasher reads from a String value.
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum InputEncoding {
+    /// The bytes of the String, as polars keeps them.
+    #[default]
+    Utf8,
+    /// Two bytes for each UTF-16 code unit, least significant byte first.
+    Utf16le,
+    /// One byte for each character. A character above U+00FF has no byte.
+    Latin1,
+    /// The text is hexadecimal digits, two for each byte.
+    Hex,
+    /// The text is base64 with the standard alphabet and padding.
+    Base64,
+}
+
+/// What a hasher does with a value that does not decode.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDecodeError {
+    /// The expression fails, and the error gives the row and the value.
+    #[default]
+    Raise,
+    /// The value gives a null hash.
+    Null,
+}
+
+impl InputEncoding {
+    fn name(self) -> &'static str {
+        match self {
+            InputEncoding::Utf8 => "utf8",
+            InputEncoding::Utf16le => "utf16le",
+            InputEncoding::Latin1 => "latin1",
+            InputEncoding::Hex => "hex",
+            InputEncoding::Base64 => "base64",
+        }
+    }
+
+    /// Writes the bytes of `value` to the end of `out`, or gives the reason that it
+    /// cannot.
+    fn write(self, value: &str, out: &mut Vec<u8>) -> Result<(), String> {
+        match self {
+            InputEncoding::Utf8 => out.extend_from_slice(value.as_bytes()),
+            InputEncoding::Utf16le => {
+                out.reserve(value.len() * 2);
+                for unit in value.encode_utf16() {
+                    out.extend_from_slice(&unit.to_le_bytes());
+                }
+            }
+            InputEncoding::Latin1 => {
+                for c in value.chars() {
+                    let byte = u8::try_from(c)
+                        .map_err(|_| format!("the character {:?} is not in Latin-1", c))?;
+                    out.push(byte);
+                }
+            }
+            InputEncoding::Hex => {
+                let start = out.len();
+                out.resize(start + value.len() / 2, 0);
+                hex::decode_to_slice(value, &mut out[start..]).map_err(|e| e.to_string())?;
+            }
+            InputEncoding::Base64 => base64::engine::general_purpose::STANDARD
+                .decode_vec(value, out)
+                .map_err(|e| e.to_string())?,
+        }
+        Ok(())
+    }
+}
+
+/// Gives the bytes of each value of `s` in `encoding`, with each null value changed to
+/// `null_bytes` if there are some.
+///
+/// Only a String column has text to encode. A Binary column already has its bytes, and
+/// a number has the bytes of [`crate::canonical`]. Therefore any other column is an
+/// error, and not a silent UTF-8.
+pub fn encode_strings(
+    s: &Series,
+    encoding: InputEncoding,
+    on_error: OnDecodeError,
+    null_bytes: Option<&[u8]>,
+) -> PolarsResult<BinaryChunked> {
+    check_encoding(encoding, s.dtype())?;
+    let ca = s.str()?;
+    let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
+    let mut bytes = Vec::new();
+    for (row, value) in ca.iter().enumerate() {
+        let Some(value) = value else {
+            match null_bytes {
+                Some(null_bytes) => builder.append_value(null_bytes),
+                None => builder.append_null(),
+            }
+            continue;
+        };
+        bytes.clear();
+        match (encoding.write(value, &mut bytes), on_error) {
+            (Ok(()), _) => builder.append_value(&bytes),
+            (Err(_), OnDecodeError::Null) => builder.append_null(),
+            (Err(e), OnDecodeError::Raise) => polars_bail!(
+                ComputeError:
+                "the value {:?} at row {} of column {:?} is not valid {}: {}",
+                shortened(value), row, s.name(), encoding.name(), e
+            ),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Checks that a column of `dtype` has text for `encoding`. UTF-8 is the bytes that a
+/// hasher reads without an encoding, and therefore it applies to any column.
+pub fn check_encoding(encoding: InputEncoding, dtype: &DataType) -> PolarsResult<()> {
+    match (encoding, dtype) {
+        (InputEncoding::Utf8, _) | (_, DataType::String | DataType::Unknown(_)) => Ok(()),
+        (encoding, dtype) => polars_bail!(
+            InvalidOperation:
+            "input_encoding {:?} applies to a `String` input, got `{}`", encoding.name(), dtype
+        ),
+    }
+}
+
+/// Gives the start of a long value, for an error. A payload can be megabytes of base64,
+/// and the message must stay readable.
+fn shortened(value: &str) -> Cow<'_, str> {
+    const CHARS: usize = 40;
+    match value.char_indices().nth(CHARS) {
+        Some((end, _)) => Cow::Owned(format!("{}...", &value[..end])),
+        None => Cow::Borrowed(value),
+    }
+}
@@ -0,0 +1,286 @@
+//! The hashes of the JVM: `hashCode` of Java, the bucket of a Hive table, and the
+//! bytes that the funnels of Guava give to a hash function.
+//!
+//! Java has no unsigned integer, and `hashCode` gives an `int`. Therefore these
+//! functions give an `Int32` with the bits of the Java value. A Java value also keeps
+//! its type: an `Integer` and a `Long` of the same value can give two different
+//! hashes. Therefore each Polars type is the Java type of its width, and an unsigned
+//! column is an error.
+
+use crate::canonical::fixed_width;
+use crate::input_encoding::{encode_strings, InputEncoding, OnDecodeError};
+use polars::chunked_array::ops::arity::unary_elementwise_values;
+use polars::prelude::*;
+
+/// The `hashCode` rules of one system. Hive and Java give the same hash to a number,
+/// but not to a string or a boolean.
+#[derive(Clone, Copy)]
+enum Rules {
+    /// `hashCode` of the boxed Java value.
+    Java,
+    /// `ObjectInspectorUtils.hashCode` of Hive, which buckets a table of
+    /// `bucketing_version` 1.
+    Hive,
+}
+
+/// `String.hashCode`. Java reads the UTF-16 code units of the string. Therefore a
+/// character above U+FFFF is two units, as in Java.
+fn string_hash_code(value: &str) -> i32 {
+    value.encode_utf16().fold(0, |hash: i32, unit| {
+        hash.wrapping_mul(31).wrapping_add(unit as i32)
+    })
+}
+
+/// `Arrays.hashCode(byte[])`. Each byte is a signed Java `byte`. Hive hashes the UTF-8
+/// bytes of a string with `Text.hashCode`, which is the same function.
+fn bytes_hash_code(value: &[u8]) -> i32 {
+    value.iter().fold(1, |hash: i32, &byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as i8 as i32)
+    })
+}
+
+/// `Long.hashCode`.
+fn long_hash_code(value: i64) -> i32 {
+    (value ^ ((value as u64) >> 32) as i64) as i32
+}
+
+/// `Float.hashCode`, which is `floatToIntBits`. Java gives one NaN for all of them, and
+/// `f32::NAN` has the same bits as that NaN.
+fn float_hash_code(value: f32) -> i32 {
+    let value = if value.is_nan() { f32::NAN } else { value };
+    value.to_bits() as i32
+}
+
+/// `Double.hashCode`, which folds `doubleToLongBits`. As in [`float_hash_code`], the
+/// bits of `f64::NAN` are the NaN of Java.
+fn double_hash_code(value: f64) -> i32 {
+    let value = if value.is_nan() { f64::NAN } else { value };
+    long_hash_code(value.to_bits() as i64)
+}
+
+/// Checks that [`hash_codes`] reads a column of `dtype`, when polars builds the plan.
+/// A `List` or an `Array` gives a hash to each element, as a hasher does. A `Struct`
+/// is the arguments of `Objects.hash`.
+pub fn check_hash_code_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::List(inner) | DataType::Array(inner, _) => check_hash_code_dtype(inner),
+        DataType::Struct(fields) => fields
+            .iter()
+            .try_for_each(|field| check_value_dtype(field.dtype(), "Java hashCode")),
+        dtype => check_value_dtype(dtype, "Java hashCode"),
+    }
+}
+
+/// Checks that [`hive_buckets`] reads each column of the Struct `dtype`.
+pub fn check_hive_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::Struct(fields) => fields
+            .iter()
+            .try_for_each(|field| check_value_dtype(field.dtype(), "Hive bucket")),
+        dtype => check_value_dtype(dtype, "Hive bucket"),
+    }
+}
+
+fn check_value_dtype(dtype: &DataType, target: &str) -> PolarsResult<()> {
+    match dtype {
+        DataType::Unknown(_)
+        | DataType::Null
+        | DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::String
+        | DataType::Binary
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _) => Ok(()),
+        dtype => Err(no_java_type(dtype, target)),
+    }
+}
+
+fn no_java_type(dtype: &DataType, target: &str) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected `String`, `Binary`, a signed integer, a float, `Boolean` or categorical \
+        input for a {}, got `{}`",
+        target,
+        dtype
+    )
+}
+
+/// The hash of each value of a column that is not nested. A null stays null.
+fn value_hash_codes(s: &Series, rules: Rules) -> PolarsResult<Int32Chunked> {
+    let strings = |ca: &StringChunked| match rules {
+        Rules::Java => unary_elementwise_values(ca, string_hash_code),
+        Rules::Hive => unary_elementwise_values(ca, |v: &str| bytes_hash_code(v.as_bytes())),
+    };
+    let out = match s.dtype() {
+        DataType::Null => Int32Chunked::full_null(s.name().clone(), s.len()),
+        DataType::Boolean => {
+            let (yes, no) = match rules {
+                Rules::Java => (1231, 1237),
+                Rules::Hive => (1, 0),
+            };
+            s.bool()?
+                .iter()
+                .map(|v| v.map(|v| if v { yes } else { no }))
+                .collect_ca(s.name().clone())
+        }
+        // A `Byte`, a `Short` and an `Integer` are their own hash.
+        DataType::Int8 | DataType::Int16 | DataType::Int32 => {
+            s.cast(&DataType::Int32)?.i32()?.clone()
+        }
+        DataType::Int64 => unary_elementwise_values(s.i64()?, long_hash_code),
+        DataType::Float32 => unary_elementwise_values(s.f32()?, float_hash_code),
+        DataType::Float64 => unary_elementwise_values(s.f64()?, double_hash_code),
+        DataType::String => strings(s.str()?),
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            strings(s.cast(&DataType::String)?.str()?)
+        }
+        DataType::Binary => unary_elementwise_values(s.binary()?, bytes_hash_code),
+        dtype => {
+            let target = match rules {
+                Rules::Java => "Java hashCode",
+                Rules::Hive => "Hive bucket",
+            };
+            return Err(no_java_type(dtype, target));
+        }
+    };
+    Ok(out.with_name(s.name().clone()))
+}
+
+/// Folds the hashes of the fields of each row as `31 * hash + field`, from `start`. A
+/// null field adds 0, as `Objects.hashCode(null)` does.
+fn combined_hash_codes(s: &Series, rules: Rules, start: i32) -> PolarsResult<Vec<i32>> {
+    let fields = s
+        .struct_()?
+        .fields_as_series()
+        .iter()
+        .map(|field| Ok(value_hash_codes(field, rules)?.rechunk().into_owned()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let out = (0..s.len())
+        .map(|row| {
+            fields.iter().fold(start, |hash, field| {
+                hash.wrapping_mul(31)
+                    .wrapping_add(field.get(row).unwrap_or(0))
+            })
+        })
+        .collect();
+    Ok(out)
+}
+
+/// Gives the Java `hashCode` of each value of `s`. A Struct row is
+/// `Objects.hash(fields...)`, and a null Struct row is null.
+pub fn hash_codes(s: &Series) -> PolarsResult<Int32Chunked> {
+    if !matches!(s.dtype(), DataType::Struct(_)) {
+        return value_hash_codes(s, Rules::Java);
+    }
+    // `Objects.hash` is `Arrays.hashCode` of its arguments, which starts at 1.
+    let hashes = combined_hash_codes(s, Rules::Java, 1)?;
+    let out = hashes
+        .into_iter()
+        .zip(s.is_null().iter())
+        .map(|(hash, null)| (null != Some(true)).then_some(hash))
+        .collect_ca(s.name().clone());
+    Ok(out)
+}
+
+/// Gives the bucket of each row of the Struct `s` in a Hive table of `buckets`
+/// buckets.
+///
+/// Hive starts at 0 and adds each column as `31 * hash + column`. A null column adds
+/// 0. The bucket is the hash without its sign bit, modulo the number of buckets. This
+/// is `getBucketNumber` of a table of `bucketing_version` 1.
+pub fn hive_buckets(s: &Series, buckets: i32) -> PolarsResult<Int32Chunked> {
+    let out = combined_hash_codes(s, Rules::Hive, 0)?
+        .into_iter()
+        .map(|hash| (hash & i32::MAX) % buckets)
+        .collect::<Vec<_>>();
+    Ok(Int32Chunked::from_vec(s.name().clone(), out))
+}
+
+/// Checks that [`funnel_bytes`] reads a column of `dtype`, when polars builds the plan.
+/// A `List` or an `Array` gives a hash to each element, as a hasher does.
+pub fn check_funnel_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::List(inner) | DataType::Array(inner, _) => check_funnel_dtype(inner),
+        DataType::Struct(fields) if fields.is_empty() => Err(no_java_type(dtype, "Guava hash")),
+        DataType::Struct(fields) => fields
+            .iter()
+            .try_for_each(|field| check_value_dtype(field.dtype(), "Guava hash")),
+        dtype => check_value_dtype(dtype, "Guava hash"),
+    }
+}
+
+/// Gives the bytes that a Guava `Hasher` reads for each value of `s`. A Struct row is
+/// the bytes of its fields in order, as a `Funnel` that puts each field. A null value,
+/// and a Struct row with a null field, give null.
+///
+/// A `put` of a number writes the bytes of its Java type, least significant byte
+/// first, and a float keeps its raw bits. A `putString` writes the bytes of the
+/// string in its charset, which is `encoding` here.
+pub fn funnel_bytes(
+    s: &Series,
+    encoding: InputEncoding,
+    on_error: OnDecodeError,
+) -> PolarsResult<BinaryChunked> {
+    let DataType::Struct(_) = s.dtype() else {
+        return value_funnel_bytes(s, encoding, on_error);
+    };
+    let fields = s
+        .struct_()?
+        .fields_as_series()
+        .iter()
+        .map(|field| {
+            Ok(value_funnel_bytes(field, encoding, on_error)?
+                .rechunk()
+                .into_owned())
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
+    let mut bytes = Vec::new();
+    for (row, null) in s.is_null().iter().enumerate() {
+        bytes.clear();
+        let complete = null != Some(true)
+            && fields.iter().all(|field| match field.get(row) {
+                Some(value) => {
+                    bytes.extend_from_slice(value);
+                    true
+                }
+                None => false,
+            });
+        if complete {
+            builder.append_value(&bytes);
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn value_funnel_bytes(
+    s: &Series,
+    encoding: InputEncoding,
+    on_error: OnDecodeError,
+) -> PolarsResult<BinaryChunked> {
+    let out = match s.dtype() {
+        DataType::Null => BinaryChunked::full_null(s.name().clone(), s.len()),
+        DataType::String => encode_strings(s, encoding, on_error, None)?,
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            encode_strings(&s.cast(&DataType::String)?, encoding, on_error, None)?
+        }
+        DataType::Binary => s.binary()?.clone(),
+        // `putBoolean` writes one byte.
+        DataType::Boolean => fixed_width(&s.cast(&DataType::UInt8)?.u8()?.clone(), |v| [v]),
+        DataType::Int8 => fixed_width(s.i8()?, i8::to_le_bytes),
+        DataType::Int16 => fixed_width(s.i16()?, i16::to_le_bytes),
+        DataType::Int32 => fixed_width(s.i32()?, i32::to_le_bytes),
+        DataType::Int64 => fixed_width(s.i64()?, i64::to_le_bytes),
+        DataType::Float32 => fixed_width(s.f32()?, f32::to_le_bytes),
+        DataType::Float64 => fixed_width(s.f64()?, f64::to_le_bytes),
+        dtype => return Err(no_java_type(dtype, "Guava hash")),
+    };
+    Ok(out)
+}
@@ -1,11 +1,23 @@
+mod affix;
+mod canonical;
+mod cassandra;
+mod clickhouse;
+mod crc_hashers;
 mod expressions;
 mod geohashers;
 mod h3;
 mod hmac_hashers;
+mod iceberg;
+mod input_encoding;
+mod jvm;
 mod murmurhash_hashers;
+mod postgres;
+mod registry;
 mod row_encode;
 mod sha_hashers;
+mod sha_lanes;
 mod shared;
+mod spark;
 mod timehashers;
 mod xxhash_hashers;
 
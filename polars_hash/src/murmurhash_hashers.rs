@@ -1,3 +1,4 @@
+use crate::registry::{Algorithm, Hasher, Seed};
 use mur3::murmurhash3_x64_128;
 use mur3::murmurhash3_x86_32;
 
@@ -12,3 +13,157 @@ pub fn murmurhash3_128(value: &[u8], seed: u32) -> u128 {
 
     (h1 as u128) | ((h2 as u128) << 64)
 }
+
+/// The seed and the multiplier of `Utils.murmur2` in the Kafka clients. That function
+/// is MurmurHash2 with this seed, and the `mur3` crate has no MurmurHash2.
+const KAFKA_SEED: u32 = 0x9747b28c;
+const KAFKA_M: u32 = 0x5bd1e995;
+
+/// `Utils.murmur2` of the Kafka clients, as the bits of the Java `int` that it gives.
+pub fn murmurhash2_kafka(value: &[u8]) -> u32 {
+    let mut h = KAFKA_SEED ^ value.len() as u32;
+    let mut blocks = value.chunks_exact(4);
+    for block in &mut blocks {
+        // Java adds the four bytes, each one shifted to its own byte of the `int`.
+        // That is a little-endian read.
+        let mut k = u32::from_le_bytes(block.try_into().unwrap());
+        k = k.wrapping_mul(KAFKA_M);
+        k ^= k >> 24;
+        k = k.wrapping_mul(KAFKA_M);
+        h = h.wrapping_mul(KAFKA_M);
+        h ^= k;
+    }
+    let tail = blocks.remainder();
+    if tail.len() >= 3 {
+        h ^= (tail[2] as u32) << 16;
+    }
+    if tail.len() >= 2 {
+        h ^= (tail[1] as u32) << 8;
+    }
+    if let Some(&first) = tail.first() {
+        h ^= first as u32;
+        h = h.wrapping_mul(KAFKA_M);
+    }
+    h ^= h >> 13;
+    h = h.wrapping_mul(KAFKA_M);
+    h ^= h >> 15;
+    h
+}
+
+/// The partition that the default partitioner of Kafka gives to a key:
+/// `Utils.toPositive(Utils.murmur2(key)) % numPartitions`. `toPositive` clears the sign
+/// bit, and it does not take the absolute value.
+pub fn kafka_default_partition(value: &[u8], partitions: i32) -> i32 {
+    (murmurhash2_kafka(value) & 0x7fff_ffff) as i32 % partitions
+}
+
+const C1: u64 = 0x87c3_7b91_1142_53d5;
+const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^= k >> 33;
+    k
+}
+
+/// MurmurHash3 x64 128 as the Java ports compute it. `seed` starts both halves. The
+/// Java hashers keep the halves in `long` fields, and therefore a negative `int` seed
+/// has its sign in the bits above it.
+///
+/// With `signed_tail`, each byte of the tail is read as a signed `byte`, as
+/// `MurmurHash.hash3_x64_128` of Cassandra reads it. The cast to `long` copies its
+/// sign bit to the bits above it. Therefore a key with a byte of 0x80 or more in its
+/// last 15 bytes gives a different hash from the reference MurmurHash3. The blocks of
+/// 16 bytes are the same as the reference.
+fn murmurhash3_x64_128_java(value: &[u8], seed: u64, signed_tail: bool) -> (u64, u64) {
+    let (mut h1, mut h2) = (seed, seed);
+    let mut blocks = value.chunks_exact(16);
+    for block in &mut blocks {
+        let k1 = u64::from_le_bytes(block[..8].try_into().unwrap());
+        let k2 = u64::from_le_bytes(block[8..].try_into().unwrap());
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+        h1 = h1.rotate_left(27).wrapping_add(h2);
+        h1 = h1.wrapping_mul(5).wrapping_add(0x52dc_e729);
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+        h2 = h2.rotate_left(31).wrapping_add(h1);
+        h2 = h2.wrapping_mul(5).wrapping_add(0x3849_5ab5);
+    }
+    let tail = blocks.remainder();
+    let byte = |i: usize| match signed_tail {
+        true => tail[i] as i8 as i64 as u64,
+        false => tail[i] as u64,
+    };
+    let (mut k1, mut k2) = (0u64, 0u64);
+    for i in (8..tail.len()).rev() {
+        k2 ^= byte(i) << ((i - 8) * 8);
+    }
+    if tail.len() > 8 {
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+    }
+    for i in (0..tail.len().min(8)).rev() {
+        k1 ^= byte(i) << (i * 8);
+    }
+    if !tail.is_empty() {
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+    }
+    h1 ^= value.len() as u64;
+    h2 ^= value.len() as u64;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix64(h1);
+    h2 = fmix64(h2);
+    h1 = h1.wrapping_add(h2);
+    (h1, h2.wrapping_add(h1))
+}
+
+/// The first half of MurmurHash3 x64 128 with seed 0, as `MurmurHash.hash3_x64_128` of
+/// Cassandra computes it, with the signed tail of that function.
+pub fn murmurhash3_64_cassandra(value: &[u8]) -> u64 {
+    murmurhash3_x64_128_java(value, 0, true).0
+}
+
+/// The token that `Murmur3Partitioner` of Cassandra gives to a partition key. An empty
+/// key is the minimum token. The partitioner keeps `i64::MIN` for it, and therefore a
+/// hash of `i64::MIN` is `i64::MAX`.
+pub fn murmur3_partitioner_token(key: &[u8]) -> i64 {
+    if key.is_empty() {
+        return i64::MIN;
+    }
+    match murmurhash3_64_cassandra(key) as i64 {
+        i64::MIN => i64::MAX,
+        token => token,
+    }
+}
+
+/// `Hashing.murmur3_32_fixed(seed).hashBytes(value).asInt()` of Guava. This is the
+/// reference MurmurHash3 x86 32, read as a Java `int`.
+pub fn murmurhash3_32_guava(value: &[u8], seed: i32) -> i32 {
+    murmurhash3_x86_32(value, seed as u32) as i32
+}
+
+/// `Hashing.murmur3_128(seed).hashBytes(value).asLong()` of Guava. `asLong` reads the
+/// first 8 bytes of the digest, and these are `h1`, least significant byte first.
+pub fn murmurhash3_128_guava(value: &[u8], seed: i32) -> i64 {
+    murmurhash3_x64_128_java(value, seed as i64 as u64, false).0 as i64
+}
+
+pub static ALGORITHMS: &[Algorithm] = &[
+    Algorithm {
+        name: "murmur32",
+        seed: Seed::U32,
+        hasher: Hasher::U32(|v, seed| murmurhash3_32(v, seed as u32)),
+    },
+    Algorithm {
+        name: "murmur128",
+        seed: Seed::U32,
+        hasher: Hasher::U128(|v, seed| murmurhash3_128(v, seed as u32)),
+    },
+    Algorithm {
+        name: "kafka_murmur2",
+        seed: Seed::None,
+        hasher: Hasher::U32(|v, _| murmurhash2_kafka(v)),
+    },
+];
@@ -0,0 +1,270 @@
+//! The hash partition of a row, as PostgreSQL computes it for a table that is
+//! `PARTITION BY HASH`.
+//!
+//! PostgreSQL hashes each column of the partition key with the extended hash function
+//! of its type, with a fixed seed. It skips a null column, and it combines the other
+//! hashes into one `uint64`. The remainder of that value by the modulus is the
+//! partition. Each hash function is `hash_any_extended` of `common/hashfn.c`, a port
+//! of lookup3 of Bob Jenkins, or a variant of it for one 32-bit word.
+//!
+//! The values follow the rules of `row_encode`. PostgreSQL puts the integer types in
+//! one hash family, and `int8` hashes a value that fits in 32 bits as `int4` does. Its
+//! `float4` hash also widens the value to `float8`. Therefore, as in `row_encode`, an
+//! `Int32` and an `Int64` of the same value give the same hash, and so do the floats.
+//! A temporal value is read in its own unit, as [`to_micros`] does.
+
+use crate::canonical::normalize_float;
+use crate::row_encode::to_micros;
+use crate::shared::at_row;
+use polars::chunked_array::ops::arity::unary_elementwise_values;
+use polars::prelude::*;
+
+/// `HASH_PARTITION_SEED` of `partitioning/partbounds.h`.
+const HASH_PARTITION_SEED: u64 = 0x7a5b_2236_7996_dcfd;
+
+/// The days from 1970-01-01 to 2000-01-01. A PostgreSQL `date` counts from 2000.
+const POSTGRES_EPOCH_DAYS: i64 = 10_957;
+
+/// `mix()` of lookup3.
+fn mix(a: &mut u32, b: &mut u32, c: &mut u32) {
+    *a = a.wrapping_sub(*c);
+    *a ^= c.rotate_left(4);
+    *c = c.wrapping_add(*b);
+    *b = b.wrapping_sub(*a);
+    *b ^= a.rotate_left(6);
+    *a = a.wrapping_add(*c);
+    *c = c.wrapping_sub(*b);
+    *c ^= b.rotate_left(8);
+    *b = b.wrapping_add(*a);
+    *a = a.wrapping_sub(*c);
+    *a ^= c.rotate_left(16);
+    *c = c.wrapping_add(*b);
+    *b = b.wrapping_sub(*a);
+    *b ^= a.rotate_left(19);
+    *a = a.wrapping_add(*c);
+    *c = c.wrapping_sub(*b);
+    *c ^= b.rotate_left(4);
+    *b = b.wrapping_add(*a);
+}
+
+/// `final()` of lookup3. It gives the 64 bits of `b` and `c`, as the extended
+/// functions of PostgreSQL do.
+fn finish(mut a: u32, mut b: u32, mut c: u32) -> u64 {
+    c ^= b;
+    c = c.wrapping_sub(b.rotate_left(14));
+    a ^= c;
+    a = a.wrapping_sub(c.rotate_left(11));
+    b ^= a;
+    b = b.wrapping_sub(a.rotate_left(25));
+    c ^= b;
+    c = c.wrapping_sub(b.rotate_left(16));
+    a ^= c;
+    a = a.wrapping_sub(c.rotate_left(4));
+    b ^= a;
+    b = b.wrapping_sub(a.rotate_left(14));
+    c ^= b;
+    c = c.wrapping_sub(b.rotate_left(24));
+    ((b as u64) << 32) | c as u64
+}
+
+/// The start state of lookup3 for a key of `len` bytes, with the seed mixed in.
+fn start(len: u32, seed: u64) -> (u32, u32, u32) {
+    let init = 0x9e37_79b9u32.wrapping_add(len).wrapping_add(3_923_095);
+    let (mut a, mut b, mut c) = (init, init, init);
+    if seed != 0 {
+        a = a.wrapping_add((seed >> 32) as u32);
+        b = b.wrapping_add(seed as u32);
+        mix(&mut a, &mut b, &mut c);
+    }
+    (a, b, c)
+}
+
+/// `hash_bytes_extended`, which `hash_any_extended` calls. This is the little-endian
+/// form, which all the usual servers run.
+fn hash_bytes_extended(value: &[u8], seed: u64) -> u64 {
+    let (mut a, mut b, mut c) = start(value.len() as u32, seed);
+    let word = |bytes: &[u8]| u32::from_le_bytes(bytes.try_into().unwrap());
+    let mut blocks = value.chunks_exact(12);
+    for block in &mut blocks {
+        a = a.wrapping_add(word(&block[..4]));
+        b = b.wrapping_add(word(&block[4..8]));
+        c = c.wrapping_add(word(&block[8..]));
+        mix(&mut a, &mut b, &mut c);
+    }
+    // The tail fills `a`, then `b`, then `c` from its low byte. The low byte of `c` is
+    // for the length, and therefore the bytes of `c` start one byte higher.
+    for (i, &byte) in blocks.remainder().iter().enumerate() {
+        let byte = byte as u32;
+        match i {
+            0..=3 => a = a.wrapping_add(byte << (8 * i)),
+            4..=7 => b = b.wrapping_add(byte << (8 * (i - 4))),
+            _ => c = c.wrapping_add(byte << (8 * (i - 7))),
+        }
+    }
+    finish(a, b, c)
+}
+
+/// `hash_bytes_uint32_extended`, which hashes one 32-bit word.
+fn hash_uint32_extended(value: u32, seed: u64) -> u64 {
+    let (a, b, c) = start(4, seed);
+    finish(a.wrapping_add(value), b, c)
+}
+
+/// `hashint8extended`. A value that fits in an `int4` gives the hash of `hashint4`,
+/// because the high half folds to 0 for it.
+fn hash_int8(value: i64) -> u64 {
+    let high = (value >> 32) as u32;
+    let low = value as u32 ^ if value >= 0 { high } else { !high };
+    hash_uint32_extended(low, HASH_PARTITION_SEED)
+}
+
+/// `hashfloat8extended`. It gives the seed for a zero, so that `-0.0` and `0.0` are
+/// one value, and it hashes one NaN for all of them.
+fn hash_float8(value: f64) -> u64 {
+    if value == 0.0 {
+        return HASH_PARTITION_SEED;
+    }
+    hash_bytes_extended(&normalize_float(value).to_le_bytes(), HASH_PARTITION_SEED)
+}
+
+/// `hash_combine64` of `common/hashfn.h`.
+fn hash_combine64(a: u64, b: u64) -> u64 {
+    a ^ b
+        .wrapping_add(0x49a0_f4dd_15e5_a8e3)
+        .wrapping_add(a << 54)
+        .wrapping_add(a >> 7)
+}
+
+/// Checks that [`partition_remainders`] reads a column of `dtype`, when polars builds
+/// the plan.
+pub fn check_partition_key_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::Unknown(_)
+        | DataType::Null
+        | DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::String
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _)
+        | DataType::Binary
+        | DataType::Date
+        | DataType::Time
+        | DataType::Datetime(_, _) => Ok(()),
+        dtype => Err(no_partition_key(dtype)),
+    }
+}
+
+fn no_partition_key(dtype: &DataType) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected `String`, `Binary`, an integer, a float, `Boolean`, `Date`, `Time`, \
+        `Datetime` or categorical input for a PostgreSQL partition key, got `{}`",
+        dtype
+    )
+}
+
+/// The hash of each value of `s`, as the hash function of its PostgreSQL type gives
+/// it with the partition seed. A null stays null.
+fn column_hashes(s: &Series) -> PolarsResult<UInt64Chunked> {
+    let int8 = |s: &Series| -> PolarsResult<UInt64Chunked> {
+        Ok(unary_elementwise_values(
+            s.cast(&DataType::Int64)?.i64()?,
+            hash_int8,
+        ))
+    };
+    let bytes = |ca: &BinaryChunked| {
+        unary_elementwise_values(ca, |v: &[u8]| hash_bytes_extended(v, HASH_PARTITION_SEED))
+    };
+    let out = match s.dtype() {
+        DataType::Null => UInt64Chunked::full_null(s.name().clone(), s.len()),
+        // A `bigint` cannot hold a larger value. Therefore a table cannot have it.
+        DataType::UInt64 => {
+            let ca = s.u64()?;
+            if let Some(row) = ca.iter().position(|v| v > Some(i64::MAX as u64)) {
+                return Err(at_row(
+                    polars_err!(
+                        ComputeError:
+                        "the value {} is out of the range of a PostgreSQL `bigint`",
+                        ca.get(row).unwrap()
+                    ),
+                    row,
+                    s.name(),
+                ));
+            }
+            int8(s)?
+        }
+        // A `boolean` uses the hash of `char`, and `true` is the byte 1.
+        DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32 => int8(s)?,
+        DataType::Float32 | DataType::Float64 => {
+            unary_elementwise_values(s.cast(&DataType::Float64)?.f64()?, hash_float8)
+        }
+        DataType::String => bytes(&s.str()?.as_binary()),
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            bytes(&s.cast(&DataType::String)?.str()?.as_binary())
+        }
+        DataType::Binary => bytes(s.binary()?),
+        DataType::Date => unary_elementwise_values(s.to_physical_repr().i32()?, |days| {
+            hash_int8(days as i64 - POSTGRES_EPOCH_DAYS)
+        }),
+        // A `time` counts microseconds from midnight.
+        DataType::Time => unary_elementwise_values(s.to_physical_repr().i64()?, |v| {
+            hash_int8(v.div_euclid(1_000))
+        }),
+        // A `timestamp` and a `timestamptz` count microseconds from 2000-01-01. A
+        // time in nanoseconds is rounded down to its microsecond.
+        DataType::Datetime(unit, _) => {
+            let epoch = POSTGRES_EPOCH_DAYS * 86_400_000_000;
+            let micros = to_micros(s.to_physical_repr().i64()?, unit);
+            unary_elementwise_values(&micros, |v| hash_int8(v.wrapping_sub(epoch)))
+        }
+        dtype => return Err(no_partition_key(dtype)),
+    };
+    Ok(out)
+}
+
+/// Gives the remainder of the hash partition of each row of the Struct `s`, for the
+/// modulus `modulus`. This is the partition whose `FOR VALUES WITH (MODULUS m,
+/// REMAINDER r)` takes the row.
+///
+/// PostgreSQL skips a null column. Therefore a row with nulls has a partition, and a
+/// row of nulls only is in the partition of remainder 0. A null Struct row is such a
+/// row.
+pub fn partition_remainders(s: &Series, modulus: i32) -> PolarsResult<Int32Chunked> {
+    let ca = s.struct_()?;
+    let null_rows = s.is_null().rechunk().into_owned();
+    let hashes = ca
+        .fields_as_series()
+        .iter()
+        .map(|field| Ok(column_hashes(field)?.rechunk().into_owned()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let modulus = modulus as u64;
+    let out = (0..s.len())
+        .map(|row| {
+            if null_rows.get(row) == Some(true) {
+                return 0;
+            }
+            let hash = hashes
+                .iter()
+                .filter_map(|column| column.get(row))
+                .fold(0, hash_combine64);
+            (hash % modulus) as i32
+        })
+        .collect::<Vec<_>>();
+    Ok(Int32Chunked::from_vec(s.name().clone(), out))
+}
@@ -0,0 +1,356 @@
+//! One table of the byte hashers, for the expression that reads the name of its
+//! algorithm at run time.
+//!
+//! Each module of wrappers gives its own part of the table, next to the wrappers. A new
+//! hasher therefore goes into the table in the same change that adds it, and the
+//! `hash` expression finds it with no change here.
+//!
+//! An integer hasher takes its seed as a `u64` in the table, and [`Seed`] says the
+//! range that it accepts. An unseeded hasher ignores the argument. This gives one
+//! function type for each output type, and the check of a seed stays here.
+
+use crate::affix::Affixed;
+use crate::hmac_hashers::HmacSha256;
+use crate::sha_hashers::{check_xof_length, hex_digests};
+use crate::sha_lanes::Sha;
+use crate::shared::{
+    for_each_affixed, hash_batches_into_string, hash_bytes, hash_bytes_into_binary,
+    hash_bytes_into_string, in_tasks, signed_dtype, with_input_bytes, with_sign, InputKwargs,
+};
+use hmac::KeyInit;
+use polars::prelude::*;
+use pyo3_polars::export::polars_arrow::bitmap::Bitmap;
+use serde::Deserialize;
+
+/// The seed that a hasher accepts.
+pub enum Seed {
+    None,
+    U32,
+    U64,
+}
+
+/// The function of a hasher, and therefore the type of its output.
+pub enum Hasher {
+    /// A digest that writes hexadecimal text. It reads the parts of the value with
+    /// `update`.
+    Hex(fn(Affixed, &mut String)),
+    /// A SHA digest that hashes a column in the lanes of a vector register. The
+    /// function hashes one value, for a walk that reads one row at a time.
+    Lanes(fn(Affixed, &mut String), Sha),
+    /// An extendable-output function, which writes `length` bytes as hexadecimal text.
+    Xof(fn(Affixed, &mut String, usize)),
+    /// A MAC, which writes hexadecimal text and needs a `key`.
+    Mac(fn(Affixed, &mut String, &HmacSha256)),
+    U32(fn(&[u8], u64) -> u32),
+    U64(fn(&[u8], u64) -> u64),
+    /// A 128-bit hasher, which can also write its hash as 16 bytes.
+    U128(fn(&[u8], u64) -> u128),
+}
+
+pub struct Algorithm {
+    pub name: &'static str,
+    pub seed: Seed,
+    pub hasher: Hasher,
+}
+
+/// The arguments of the `hash` expression that are not the name. Each one applies to
+/// some of the algorithms only, and [`Algorithm::check`] rejects the others. A silent
+/// `seed` on an unseeded hasher would give the hash of seed 0 to a caller who asked
+/// for a different one.
+#[derive(Deserialize, Default)]
+pub struct Options {
+    /// A `u64` travels as its `i64` counterpart, as in `SeedKwargs64bit`.
+    pub seed: Option<i64>,
+    pub key: Option<String>,
+    pub length: Option<usize>,
+    pub return_binary: bool,
+    pub big_endian: bool,
+    pub signed: bool,
+}
+
+fn tables() -> [&'static [Algorithm]; 5] {
+    [
+        crate::sha_hashers::ALGORITHMS,
+        crate::hmac_hashers::ALGORITHMS,
+        crate::xxhash_hashers::ALGORITHMS,
+        crate::murmurhash_hashers::ALGORITHMS,
+        crate::expressions::ALGORITHMS,
+    ]
+}
+
+/// Finds an algorithm by name. The error lists each name, because the usual cause is
+/// a spelling in a configuration file that the caller cannot see from here.
+pub fn lookup(name: &str) -> PolarsResult<&'static Algorithm> {
+    let mut algorithms = tables().into_iter().flatten();
+    if let Some(algorithm) = algorithms.find(|a| a.name == name) {
+        return Ok(algorithm);
+    }
+    let names: Vec<&str> = tables().into_iter().flatten().map(|a| a.name).collect();
+    polars_bail!(
+        InvalidOperation:
+        "unknown hash algorithm {:?}, expected one of: {}", name, names.join(", ")
+    )
+}
+
+impl Algorithm {
+    /// Rejects an argument that this algorithm does not read, and a missing argument
+    /// that it needs. The output type function calls this, so a bad call fails before
+    /// the query reads any data.
+    pub fn check(&self, options: &Options) -> PolarsResult<()> {
+        let name = self.name;
+        match (&self.seed, options.seed) {
+            (Seed::None, Some(_)) => {
+                polars_bail!(InvalidOperation: "hash algorithm {:?} takes no seed", name)
+            }
+            (Seed::U32, Some(seed)) if !(0..=u32::MAX as i64).contains(&seed) => {
+                polars_bail!(InvalidOperation: "hash algorithm {:?} needs a u32 seed, got {}", name, seed)
+            }
+            _ => {}
+        }
+        match (&self.hasher, &options.key) {
+            (Hasher::Mac(_), None) => {
+                polars_bail!(InvalidOperation: "hash algorithm {:?} needs a key", name)
+            }
+            (Hasher::Mac(_), Some(_)) | (_, None) => {}
+            (_, Some(_)) => {
+                polars_bail!(InvalidOperation: "hash algorithm {:?} takes no key", name)
+            }
+        }
+        match (&self.hasher, options.length) {
+            (Hasher::Xof(_), None) => {
+                polars_bail!(InvalidOperation: "hash algorithm {:?} needs a length", name)
+            }
+            (Hasher::Xof(_), Some(length)) => check_xof_length(length)?,
+            (_, None) => {}
+            (_, Some(_)) => {
+                polars_bail!(InvalidOperation: "hash algorithm {:?} takes no length", name)
+            }
+        }
+        if (options.return_binary || options.big_endian) && !matches!(self.hasher, Hasher::U128(_))
+        {
+            polars_bail!(
+                InvalidOperation:
+                "hash algorithm {:?} has no binary output, only the 128-bit algorithms do", name
+            )
+        }
+        if options.big_endian && !options.return_binary {
+            polars_bail!(InvalidOperation: "a byte order applies to a binary output only")
+        }
+        // The method of `xxh3_128` has a byte order for `XXH128_canonicalFromHash`. No
+        // other method has one, so a big-endian `murmur128` here would be bytes that
+        // no method gives.
+        if options.big_endian && name != "xxh3_128" {
+            polars_bail!(
+                InvalidOperation:
+                "hash algorithm {:?} has no byte order, only \"xxh3_128\" does", name
+            )
+        }
+        if options.signed {
+            match self.hasher {
+                Hasher::Hex(_) | Hasher::Lanes(_, _) | Hasher::Xof(_) | Hasher::Mac(_) => {
+                    polars_bail!(
+                        InvalidOperation: "hash algorithm {:?} has no integer output", name
+                    )
+                }
+                _ if options.return_binary => {
+                    polars_bail!(InvalidOperation: "a binary output has no sign")
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn dtype(&self, options: &Options) -> DataType {
+        let dtype = match self.hasher {
+            Hasher::Hex(_) | Hasher::Lanes(_, _) | Hasher::Xof(_) | Hasher::Mac(_) => {
+                DataType::String
+            }
+            Hasher::U32(_) => DataType::UInt32,
+            Hasher::U64(_) => DataType::UInt64,
+            Hasher::U128(_) if options.return_binary => DataType::Binary,
+            Hasher::U128(_) => DataType::UInt128,
+        };
+        if options.signed {
+            signed_dtype(dtype)
+        } else {
+            dtype
+        }
+    }
+
+    /// Hashes `s`. The walks are the ones that the expression of each algorithm uses,
+    /// so a name gives the same output as the expression of that name.
+    pub fn hash(&self, s: &Series, options: &Options, input: &InputKwargs) -> PolarsResult<Series> {
+        self.check(options)?;
+        let seed = options.seed.unwrap_or(0) as u64;
+        let out = match self.hasher {
+            Hasher::Hex(f) => hash_bytes_into_string(s, input, f)?.into_series(),
+            Hasher::Lanes(f, sha) => {
+                hash_batches_into_string(s, input, f, |ca| hex_digests(ca, sha))?.into_series()
+            }
+            Hasher::Xof(f) => {
+                let length = options.length.unwrap_or_default();
+                hash_bytes_into_string(s, input, |v, out| f(v, out, length))?.into_series()
+            }
+            Hasher::Mac(f) => {
+                let key = options.key.as_deref().unwrap_or_default();
+                let keyed_mac = HmacSha256::new_from_slice(key.as_bytes())
+                    .map_err(|e| polars_err!(ComputeError: "invalid HMAC key: {}", e))?;
+                hash_bytes_into_string(s, input, |v, out| f(v, out, &keyed_mac))?.into_series()
+            }
+            Hasher::U32(f) => {
+                let out: UInt32Chunked = hash_bytes(s, input, |v| f(v, seed))?;
+                out.into_series()
+            }
+            Hasher::U64(f) => {
+                let out: UInt64Chunked = hash_bytes(s, input, |v| f(v, seed))?;
+                out.into_series()
+            }
+            Hasher::U128(f) => match (options.return_binary, options.big_endian) {
+                (true, true) => {
+                    hash_bytes_into_binary(s, input, |v| f(v, seed).to_be_bytes())?.into_series()
+                }
+                (true, false) => {
+                    hash_bytes_into_binary(s, input, |v| f(v, seed).to_le_bytes())?.into_series()
+                }
+                (false, _) => {
+                    let out: UInt128Chunked = hash_bytes(s, input, |v| f(v, seed))?;
+                    out.into_series()
+                }
+            },
+        };
+        with_sign(out, options.signed)
+    }
+}
+
+/// Finds each algorithm of `multi_digest`. Each one must need no argument, because
+/// the call has one set of arguments for all of them. A seeded algorithm takes its
+/// default seed, as it does in `hash`.
+pub fn lookup_all(names: &[String]) -> PolarsResult<Vec<&'static Algorithm>> {
+    polars_ensure!(
+        !names.is_empty(),
+        InvalidOperation: "multi_digest needs at least one algorithm"
+    );
+    let mut algorithms: Vec<&'static Algorithm> = Vec::with_capacity(names.len());
+    for name in names {
+        let algorithm = lookup(name)?;
+        algorithm.check(&Options::default())?;
+        // Each algorithm is a field of the Struct, and a field name must be unique.
+        polars_ensure!(
+            !algorithms.iter().any(|a| a.name == algorithm.name),
+            InvalidOperation: "hash algorithm {:?} appears more than once", name
+        );
+        algorithms.push(algorithm);
+    }
+    Ok(algorithms)
+}
+
+/// The data type of `multi_digest`: a Struct with one field for each algorithm.
+pub fn digests_dtype(algorithms: &[&'static Algorithm]) -> DataType {
+    let fields = algorithms
+        .iter()
+        .map(|a| Field::new(a.name.into(), a.dtype(&Options::default())))
+        .collect();
+    DataType::Struct(fields)
+}
+
+/// The hashes of one algorithm, which [`hash_all`] adds one row at a time.
+enum Digests {
+    Hex(fn(Affixed, &mut String), StringChunkedBuilder, String),
+    U32(fn(&[u8], u64) -> u32, Vec<Option<u32>>),
+    U64(fn(&[u8], u64) -> u64, Vec<Option<u64>>),
+    U128(fn(&[u8], u64) -> u128, Vec<Option<u128>>),
+}
+
+impl Digests {
+    fn new(algorithm: &Algorithm, len: usize) -> PolarsResult<Self> {
+        let out = match algorithm.hasher {
+            Hasher::Hex(f) | Hasher::Lanes(f, _) => Digests::Hex(
+                f,
+                StringChunkedBuilder::new(algorithm.name.into(), len),
+                String::new(),
+            ),
+            Hasher::U32(f) => Digests::U32(f, Vec::with_capacity(len)),
+            Hasher::U64(f) => Digests::U64(f, Vec::with_capacity(len)),
+            Hasher::U128(f) => Digests::U128(f, Vec::with_capacity(len)),
+            // `lookup_all` rejects these, because they need an argument.
+            Hasher::Xof(_) | Hasher::Mac(_) => polars_bail!(
+                InvalidOperation: "hash algorithm {:?} needs an argument", algorithm.name
+            ),
+        };
+        Ok(out)
+    }
+
+    fn push(&mut self, value: Option<&[u8]>) {
+        match self {
+            Digests::Hex(f, builder, digest) => match value {
+                Some(value) => {
+                    digest.clear();
+                    f(Affixed::bare(value), digest);
+                    builder.append_value(&*digest);
+                }
+                None => builder.append_null(),
+            },
+            Digests::U32(f, out) => out.push(value.map(|v| f(v, 0))),
+            Digests::U64(f, out) => out.push(value.map(|v| f(v, 0))),
+            Digests::U128(f, out) => out.push(value.map(|v| f(v, 0))),
+        }
+    }
+
+    fn finish(self, name: &str) -> Series {
+        match self {
+            Digests::Hex(_, builder, _) => builder.finish().into_series(),
+            Digests::U32(_, out) => {
+                UInt32Chunked::from_iter_options(name.into(), out.into_iter()).into_series()
+            }
+            Digests::U64(_, out) => {
+                UInt64Chunked::from_iter_options(name.into(), out.into_iter()).into_series()
+            }
+            Digests::U128(_, out) => {
+                UInt128Chunked::from_iter_options(name.into(), out.into_iter()).into_series()
+            }
+        }
+    }
+}
+
+/// Hashes each value of `s` with each of `algorithms`, and gives a Struct with one
+/// field for each algorithm.
+///
+/// The walk reads each value one time and gives it to all the hashers. A column of
+/// large values therefore leaves memory one time, and not one time for each
+/// algorithm. A null value gives a null row.
+pub fn hash_all(
+    s: &Series,
+    algorithms: &[&'static Algorithm],
+    input: &InputKwargs,
+) -> PolarsResult<StructChunked> {
+    let s = &*with_input_bytes(s, input)?;
+    in_tasks(s, None, |s, _, start| {
+        let mut columns = algorithms
+            .iter()
+            .map(|a| Digests::new(a, s.len()))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let mut validity = Vec::with_capacity(s.len());
+        for_each_affixed(s, input, start, |value| {
+            validity.push(value.is_some());
+            match value {
+                // The integer hashers read one slice. Therefore the parts go into one
+                // buffer, and all the hashers read it.
+                Some(value) => value.joined(|value| {
+                    for column in &mut columns {
+                        column.push(Some(value));
+                    }
+                }),
+                None => columns.iter_mut().for_each(|column| column.push(None)),
+            }
+            Ok(())
+        })?;
+        let fields: Vec<Series> = columns
+            .into_iter()
+            .zip(algorithms)
+            .map(|(column, a)| column.finish(a.name))
+            .collect();
+        let out = StructChunked::from_series(s.name().clone(), s.len(), fields.iter())?;
+        Ok(out.with_outer_validity(Some(Bitmap::from_iter(validity))))
+    })
+}
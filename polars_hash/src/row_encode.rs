@@ -26,6 +26,7 @@
 //! The cost of the exception stays here. [`VERSION`] keeps these bytes, and the golden
 //! vectors in the tests make sure that they do not change.
 
+use crate::canonical::normalize_float;
 use polars::prelude::*;
 // polars-core is not a direct dependency. `pyo3_polars` re-exports it, and this is
 // the same pool that polars uses for its own row hashes.
@@ -79,17 +80,11 @@ fn push_unsigned(out: &mut Vec<u8>, value: u128) {
     push_varint(out, value);
 }
 
-/// Polars reads `-0.0` and `0.0` as one value. It also reads all the NaN payloads as
-/// one value. This function does the same, because two equal rows must make the same
-/// hash.
+/// Two equal rows must make the same hash. Therefore `-0.0` and each NaN payload
+/// change as [`normalize_float`] says.
 fn push_float(out: &mut Vec<u8>, value: f64) {
-    let value = if value.is_nan() {
-        f64::NAN
-    } else {
-        value + 0.0
-    };
     out.push(TAG_FLOAT);
-    out.extend_from_slice(&value.to_be_bytes());
+    out.extend_from_slice(&normalize_float(value).to_be_bytes());
 }
 
 fn push_bytes(out: &mut Vec<u8>, tag: u8, value: &[u8]) {
@@ -175,7 +170,7 @@ fn is_valid(valid: &Option<Vec<bool>>, i: usize) -> bool {
 
 /// Gives the number of nanoseconds in one unit. Therefore a `Datetime` or a
 /// `Duration` makes the same payload for each unit.
-fn per_unit(unit: &TimeUnit) -> i128 {
+pub fn per_unit(unit: &TimeUnit) -> i128 {
     match unit {
         TimeUnit::Nanoseconds => 1,
         TimeUnit::Microseconds => 1_000,
@@ -183,6 +178,23 @@ fn per_unit(unit: &TimeUnit) -> i128 {
     }
 }
 
+/// Gives each value of a `Datetime` column of `unit` in microseconds. A time in
+/// nanoseconds is rounded down to its microsecond. PostgreSQL, Spark and Iceberg count
+/// a timestamp in microseconds.
+///
+/// A time that overflows here is far out of the range of those systems. The wrapping
+/// ops only keep such a value from a panic.
+pub fn to_micros(values: &Int64Chunked, unit: &TimeUnit) -> Int64Chunked {
+    let per_unit = per_unit(unit) as i64;
+    values.apply_values(|v| {
+        if per_unit >= 1_000 {
+            v.wrapping_mul(per_unit / 1_000)
+        } else {
+            v.div_euclid(1_000 / per_unit)
+        }
+    })
+}
+
 impl Column {
     fn prepare(s: &Series) -> PolarsResult<Self> {
         let s = s.rechunk();
@@ -260,7 +272,7 @@ impl Column {
                     .map(Column::prepare)
                     .collect::<PolarsResult<_>>()?,
             },
-            dtype => polars_bail!(InvalidOperation: "cannot encode a {} column into a row", dtype),
+            dtype => return Err(unencodable(dtype)),
         };
         Ok(column)
     }
@@ -349,6 +361,85 @@ impl Column {
     }
 }
 
+fn unencodable(dtype: &DataType) -> PolarsError {
+    polars_err!(InvalidOperation: "cannot encode a {} column into a row", dtype)
+}
+
+/// Checks that [`Column::prepare`] takes a column of `dtype`. An output type function
+/// calls this with the data type of the plan, before there is a column to prepare. A
+/// data type that polars does not know yet passes, and `prepare` checks the data.
+pub fn check_row_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::List(inner) | DataType::Array(inner, _) => check_row_dtype(inner),
+        DataType::Struct(fields) => fields.iter().try_for_each(|f| check_row_dtype(f.dtype())),
+        DataType::Unknown(_)
+        | DataType::Null
+        | DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Int128
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::UInt128
+        | DataType::Float16
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::String
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _)
+        | DataType::Binary
+        | DataType::BinaryOffset
+        | DataType::Date
+        | DataType::Time
+        | DataType::Datetime(_, _)
+        | DataType::Duration(_)
+        | DataType::Decimal(_, _) => Ok(()),
+        dtype => Err(unencodable(dtype)),
+    }
+}
+
+pub fn check_version(version: u64) -> PolarsResult<()> {
+    if version != VERSION {
+        polars_bail!(
+            InvalidOperation:
+            "unknown row encoding version {}, this build writes version {}", version, VERSION
+        );
+    }
+    Ok(())
+}
+
+/// Gives the bytes of each row of a Struct column to `f`, one row at a time.
+///
+/// A hasher that reads a Struct column calls this. The bytes of a row are the bytes
+/// that `hash_rows` writes for the fields of the struct, and `f` reads them from one
+/// buffer. Therefore the bytes of the column are never in memory at one time. A null
+/// row gives `None`, because a null input gives a null hash.
+pub fn for_each_struct_row<F>(s: &Series, version: u64, mut f: F) -> PolarsResult<()>
+where
+    F: FnMut(Option<&[u8]>) -> PolarsResult<()>,
+{
+    check_version(version)?;
+    let column = Column::prepare(s)?;
+    let Column::Struct { valid, .. } = &column else {
+        polars_bail!(InvalidOperation: "expected a `Struct` column, got `{}`", s.dtype());
+    };
+    let mut row = Vec::with_capacity(64);
+    for i in 0..s.len() {
+        if !is_valid(valid, i) {
+            f(None)?;
+            continue;
+        }
+        row.clear();
+        column.encode(i, &mut row);
+        f(Some(&row))?;
+    }
+    Ok(())
+}
+
 /// Changes each row of `inputs` into its canonical bytes.
 ///
 /// A row is the inputs in their given order. Therefore the caller sets the content of
@@ -356,12 +447,7 @@ impl Column {
 /// values. A null is one of the values that the encoding writes. Therefore a row with
 /// a null value also has a hash.
 pub fn encode_rows(inputs: &[Series], version: u64) -> PolarsResult<BinaryChunked> {
-    if version != VERSION {
-        polars_bail!(
-            InvalidOperation:
-            "unknown row encoding version {}, this build writes version {}", version, VERSION
-        );
-    }
+    check_version(version)?;
 
     let rows = inputs[0].len();
     if let Some(other) = inputs.iter().find(|s| s.len() != rows) {
@@ -382,7 +468,7 @@ pub fn encode_rows(inputs: &[Series], version: u64) -> PolarsResult<BinaryChunke
 
 /// The number of rows for one task. A task must be long enough to pay for the work to
 /// start it, and short enough to let a free thread take some of the remaining rows.
-const ROWS_FOR_ONE_TASK: usize = 16_384;
+pub const ROWS_FOR_ONE_TASK: usize = 16_384;
 
 /// Encodes the rows from `start` to `end`, and gives the work to more than one thread
 /// if there are sufficient rows.
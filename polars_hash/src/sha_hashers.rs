@@ -1,59 +1,177 @@
+//! The SHA digests, as hexadecimal text.
+//!
+//! The `sha1` and `sha2` crates choose the SHA instructions of the CPU at run time,
+//! SHA-NI on x86 and the SHA2 extension on ARM, and fall back to portable code. They
+//! hash one value at a time. The `sha1` and `sha2_256` expressions hash a column of
+//! short values in the lanes of a vector register instead, by [`hex_digests`] and
+//! [`crate::sha_lanes`]. The digests are the same. [`write_hex`] writes the text of
+//! each digest with no allocation.
+//!
+//! Each digest reads the prefix, the value and the suffix of an [`Affixed`] value as
+//! one message, by three calls of `update`.
+
+use crate::affix::Affixed;
+use crate::registry::{Algorithm, Hasher, Seed};
+use crate::sha_lanes::{digests, Sha};
+use crate::shared::write_hex;
+use polars::prelude::*;
 use sha1::Sha1;
+use sha2::digest::Output;
 use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
 use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
 use shake::{ExtendableOutput, Shake128, Update, XofReader};
-use std::fmt::Write;
 
-pub fn sha1_hash(value: &[u8], output: &mut String) {
-    let hash = Sha1::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+/// Gives the digest of the parts of `value`, in order.
+fn digest<D: Digest>(value: Affixed) -> Output<D> {
+    let mut hasher = D::new();
+    for part in value.parts() {
+        Digest::update(&mut hasher, part);
+    }
+    hasher.finalize()
+}
+
+pub fn sha1_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha1>(value);
+    write_hex(&hash, output)
+}
+
+pub fn sha2_256_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha256>(value);
+    write_hex(&hash, output)
+}
+
+/// Gives the digest of each value of `ca` as hexadecimal text, from the lanes of
+/// [`crate::sha_lanes`]. A null value gives a null digest.
+pub fn hex_digests(ca: &BinaryChunked, sha: Sha) -> StringChunked {
+    let values: Vec<&[u8]> = ca.iter().flatten().collect();
+    let mut hashes = digests(&values, sha).into_iter();
+    let mut builder = StringChunkedBuilder::new(ca.name().clone(), ca.len());
+    let mut digest = String::new();
+    for value in ca.iter() {
+        match value {
+            Some(_) => {
+                let hash = hashes.next().expect("a digest for each value");
+                digest.clear();
+                write_hex(&hash[..sha.len()], &mut digest);
+                builder.append_value(&digest);
+            }
+            None => builder.append_null(),
+        }
+    }
+    builder.finish()
 }
 
-pub fn sha2_256_hash(value: &[u8], output: &mut String) {
-    let hash = Sha256::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+pub fn sha2_512_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha512>(value);
+    write_hex(&hash, output)
 }
 
-pub fn sha2_512_hash(value: &[u8], output: &mut String) {
-    let hash = Sha512::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+pub fn sha2_384_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha384>(value);
+    write_hex(&hash, output)
 }
 
-pub fn sha2_384_hash(value: &[u8], output: &mut String) {
-    let hash = Sha384::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+pub fn sha2_224_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha224>(value);
+    write_hex(&hash, output)
 }
 
-pub fn sha2_224_hash(value: &[u8], output: &mut String) {
-    let hash = Sha224::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+pub fn sha3_256_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha3_256>(value);
+    write_hex(&hash, output)
 }
 
-pub fn sha3_256_hash(value: &[u8], output: &mut String) {
-    let hash = Sha3_256::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+pub fn sha3_512_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha3_512>(value);
+    write_hex(&hash, output)
 }
 
-pub fn sha3_512_hash(value: &[u8], output: &mut String) {
-    let hash = Sha3_512::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+pub fn sha3_384_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha3_384>(value);
+    write_hex(&hash, output)
 }
 
-pub fn sha3_384_hash(value: &[u8], output: &mut String) {
-    let hash = Sha3_384::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+pub fn sha3_224_hash(value: Affixed, output: &mut String) {
+    let hash = digest::<Sha3_224>(value);
+    write_hex(&hash, output)
 }
 
-pub fn sha3_224_hash(value: &[u8], output: &mut String) {
-    let hash = Sha3_224::digest(value);
-    write!(output, "{}", hex::encode(hash)).unwrap()
+/// Checks the length of a SHAKE digest. A length of 0 gives an empty string for each
+/// row, which is not a hash, and therefore it is a mistake in the call.
+pub fn check_xof_length(length: usize) -> PolarsResult<()> {
+    if length == 0 {
+        polars_bail!(InvalidOperation: "expected a length of at least 1 byte, got 0");
+    }
+    Ok(())
 }
 
-pub fn sha3_shake128_hash(value: &[u8], output: &mut String, length: usize) {
+pub fn sha3_shake128_hash(value: Affixed, output: &mut String, length: usize) {
     let mut hasher = Shake128::default();
-    hasher.update(value);
+    for part in value.parts() {
+        hasher.update(part);
+    }
     let mut reader = hasher.finalize_xof();
-    let mut result = vec![0u8; length];
-    reader.read(&mut result);
-    write!(output, "{}", hex::encode(result)).unwrap()
+    // The length comes from the caller. Therefore the output is read in blocks, and
+    // not into a `Vec` of that length for each row.
+    let mut block = [0u8; 64];
+    let mut remaining = length;
+    while remaining > 0 {
+        let block = &mut block[..remaining.min(64)];
+        reader.read(block);
+        write_hex(block, output);
+        remaining -= block.len();
+    }
 }
+
+pub static ALGORITHMS: &[Algorithm] = &[
+    Algorithm {
+        name: "sha1",
+        seed: Seed::None,
+        hasher: Hasher::Lanes(sha1_hash, Sha::Sha1),
+    },
+    Algorithm {
+        name: "sha2_224",
+        seed: Seed::None,
+        hasher: Hasher::Hex(sha2_224_hash),
+    },
+    Algorithm {
+        name: "sha2_256",
+        seed: Seed::None,
+        hasher: Hasher::Lanes(sha2_256_hash, Sha::Sha256),
+    },
+    Algorithm {
+        name: "sha2_384",
+        seed: Seed::None,
+        hasher: Hasher::Hex(sha2_384_hash),
+    },
+    Algorithm {
+        name: "sha2_512",
+        seed: Seed::None,
+        hasher: Hasher::Hex(sha2_512_hash),
+    },
+    Algorithm {
+        name: "sha3_224",
+        seed: Seed::None,
+        hasher: Hasher::Hex(sha3_224_hash),
+    },
+    Algorithm {
+        name: "sha3_256",
+        seed: Seed::None,
+        hasher: Hasher::Hex(sha3_256_hash),
+    },
+    Algorithm {
+        name: "sha3_384",
+        seed: Seed::None,
+        hasher: Hasher::Hex(sha3_384_hash),
+    },
+    Algorithm {
+        name: "sha3_512",
+        seed: Seed::None,
+        hasher: Hasher::Hex(sha3_512_hash),
+    },
+    Algorithm {
+        name: "sha3_shake128",
+        seed: Seed::None,
+        hasher: Hasher::Xof(sha3_shake128_hash),
+    },
+];
@@ -0,0 +1,521 @@
+//! SHA-256 and SHA-1 of many short values at one time, one value in each lane of a
+//! vector register.
+//!
+//! The `sha1` and `sha2` crates hash one value at a time, and the rounds of one block
+//! depend on each other. The rounds of two values do not. This module puts a value in
+//! each 32-bit lane of an AVX2 or an AVX-512 register, and each instruction does one
+//! step of 8 or 16 values.
+//!
+//! Each lane reads the blocks of its own value. When its value ends, the lane takes
+//! the next value, so values of different lengths share the lanes. A value of more
+//! than [`MAX_LANE_BLOCKS`] blocks goes to the crate, because one long value would
+//! keep its lane busy while the other lanes have no more work.
+//!
+//! The SHA-NI instructions hash one block in about the time that 8 AVX2 lanes take
+//! for a block of each lane, and 16 AVX-512 lanes are about twice as fast. Therefore
+//! [`digests`] uses the AVX-512 lanes where the CPU has them, then the crate where the
+//! CPU has SHA-NI, then the AVX2 lanes. The digests are the same on each path.
+
+// Another CPU uses the crate for all values, and the lanes are not built for it.
+#![cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// The longest value that a lane hashes, in blocks of 64 bytes. A longer value goes
+/// to the crate, which uses SHA-NI where the CPU has it.
+const MAX_LANE_BLOCKS: usize = 4;
+
+/// The lanes of the widest register. The state of the lanes is in arrays of this
+/// size, and a narrower register uses the first lanes only.
+const MAX_LANES: usize = 16;
+
+#[derive(Clone, Copy)]
+pub enum Sha {
+    Sha1,
+    Sha256,
+}
+
+impl Sha {
+    /// The length of the digest, in bytes.
+    pub fn len(self) -> usize {
+        match self {
+            Sha::Sha1 => 20,
+            Sha::Sha256 => 32,
+        }
+    }
+
+    fn words(self) -> usize {
+        self.len() / 4
+    }
+
+    fn initial_state(self) -> &'static [u32] {
+        match self {
+            Sha::Sha1 => &SHA1_H0,
+            Sha::Sha256 => &SHA256_H0,
+        }
+    }
+
+    /// The digest of one value, from the crate.
+    fn digest(self, value: &[u8]) -> [u8; 32] {
+        let mut out = [0; 32];
+        match self {
+            Sha::Sha1 => out[..20].copy_from_slice(&Sha1::digest(value)),
+            Sha::Sha256 => out.copy_from_slice(&Sha256::digest(value)),
+        }
+        out
+    }
+}
+
+const SHA1_H0: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+const SHA256_H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Gives the digest of each value, in the order of `values`. A SHA-1 digest is the
+/// first 20 bytes of its array.
+pub fn digests(values: &[&[u8]], sha: Sha) -> Vec<[u8; 32]> {
+    let mut out = vec![[0; 32]; values.len()];
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::arch::is_x86_feature_detected!("avx512f") {
+            // SAFETY: the CPU has AVX-512F.
+            unsafe { x86::lanes_avx512(values, sha, &mut out) };
+            return out;
+        }
+        // The crate uses SHA-NI, which is as fast as the AVX2 lanes.
+        if std::arch::is_x86_feature_detected!("avx2")
+            && !std::arch::is_x86_feature_detected!("sha")
+        {
+            // SAFETY: the CPU has AVX2.
+            unsafe { x86::lanes_avx2(values, sha, &mut out) };
+            return out;
+        }
+    }
+    for (value, out) in values.iter().zip(out.iter_mut()) {
+        *out = sha.digest(value);
+    }
+    out
+}
+
+/// The number of blocks of a value, with the padding of 1 to 64 bytes that ends in the
+/// bit length of the value.
+fn block_count(len: usize) -> usize {
+    (len + 8) / 64 + 1
+}
+
+/// Writes the block `i` of `value` and of its padding into `block`.
+fn load_block(value: &[u8], i: usize, blocks: usize, block: &mut [u8; 64]) {
+    let start = i * 64;
+    // The last block can hold the padding only, and no byte of the value.
+    let rest = value.get(start..).unwrap_or_default();
+    let len = rest.len().min(64);
+    block[..len].copy_from_slice(&rest[..len]);
+    block[len..].fill(0);
+    if (start..start + 64).contains(&value.len()) {
+        block[value.len() - start] = 0x80;
+    }
+    if i + 1 == blocks {
+        block[56..].copy_from_slice(&(value.len() as u64 * 8).to_be_bytes());
+    }
+}
+
+/// The operations that the rounds need on a register of `LANES` 32-bit lanes.
+///
+/// The methods are unsafe because each one needs the target feature of its
+/// register. The rounds call them only from a function with that feature.
+trait Lanes: Copy {
+    const LANES: usize;
+    unsafe fn splat(value: u32) -> Self;
+    unsafe fn load(words: &[u32; MAX_LANES]) -> Self;
+    /// The big-endian word `t` of the block of each lane.
+    unsafe fn word(blocks: &[[u8; 64]; MAX_LANES], t: usize) -> Self;
+    unsafe fn store(self, words: &mut [u32; MAX_LANES]);
+    unsafe fn add(self, other: Self) -> Self;
+    unsafe fn xor(self, other: Self) -> Self;
+    /// Rotates each lane right by `R` bits. `L` is `32 - R`, because a const
+    /// parameter cannot be an expression of another one.
+    unsafe fn rotr<const R: i32, const L: i32>(self) -> Self;
+    unsafe fn shr<const R: i32>(self) -> Self;
+    /// `x ^ y ^ z`.
+    unsafe fn xor3(x: Self, y: Self, z: Self) -> Self;
+    /// `(x & y) ^ (!x & z)`: each bit of `y` where `x` is 1, else of `z`.
+    unsafe fn choose(x: Self, y: Self, z: Self) -> Self;
+    /// The majority of the bits of `x`, `y` and `z`.
+    unsafe fn majority(x: Self, y: Self, z: Self) -> Self;
+}
+
+/// Gives each value to a lane, and runs `compress` on all lanes until each value has
+/// its digest. The caller checks the target feature of `V`.
+#[inline(always)]
+unsafe fn run_lanes<V: Lanes>(
+    values: &[&[u8]],
+    sha: Sha,
+    out: &mut [[u8; 32]],
+    compress: unsafe fn(&mut [[u32; MAX_LANES]; 8], &[[u8; 64]; MAX_LANES]),
+) {
+    // The value of each lane, and the block that the lane reads next.
+    let mut jobs: [Option<(usize, usize, usize)>; MAX_LANES] = [None; MAX_LANES];
+    let mut state = [[0; MAX_LANES]; 8];
+    let mut blocks = [[0; 64]; MAX_LANES];
+    for (value, out) in values.iter().zip(out.iter_mut()) {
+        if block_count(value.len()) > MAX_LANE_BLOCKS {
+            *out = sha.digest(value);
+        }
+    }
+    let mut queue = values.iter().enumerate().filter_map(|(i, value)| {
+        let blocks = block_count(value.len());
+        (blocks <= MAX_LANE_BLOCKS).then_some((i, blocks))
+    });
+    loop {
+        let mut busy = false;
+        for lane in 0..V::LANES {
+            if jobs[lane].is_none() {
+                if let Some((i, blocks)) = queue.next() {
+                    jobs[lane] = Some((i, 0, blocks));
+                    for (word, h0) in state.iter_mut().zip(sha.initial_state()) {
+                        word[lane] = *h0;
+                    }
+                }
+            }
+            if let Some((i, block, count)) = jobs[lane] {
+                load_block(values[i], block, count, &mut blocks[lane]);
+                busy = true;
+            }
+        }
+        if !busy {
+            return;
+        }
+        // A lane without a job hashes the block that it has. Its state is not read.
+        compress(&mut state, &blocks);
+        for lane in 0..V::LANES {
+            let Some((i, block, count)) = jobs[lane] else {
+                continue;
+            };
+            if block + 1 < count {
+                jobs[lane] = Some((i, block + 1, count));
+                continue;
+            }
+            for (t, word) in state[..sha.words()].iter().enumerate() {
+                out[i][4 * t..4 * t + 4].copy_from_slice(&word[lane].to_be_bytes());
+            }
+            jobs[lane] = None;
+        }
+    }
+}
+
+/// The SHA-256 compression of one block in each lane.
+///
+/// The rounds go in groups of 16, one for each word of the schedule. Each index of
+/// `w` in a group is then a constant, and the 16 words stay in registers.
+#[inline(always)]
+unsafe fn sha256_compress<V: Lanes>(
+    state: &mut [[u32; MAX_LANES]; 8],
+    blocks: &[[u8; 64]; MAX_LANES],
+) {
+    let mut w: [V; 16] = std::array::from_fn(|t| V::word(blocks, t));
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] =
+        std::array::from_fn(|i| V::load(&state[i]));
+    for group in 0..4 {
+        for j in 0..16 {
+            if group > 0 {
+                let w15 = w[(j + 1) % 16];
+                let w2 = w[(j + 14) % 16];
+                let s0 = V::xor3(w15.rotr::<7, 25>(), w15.rotr::<18, 14>(), w15.shr::<3>());
+                let s1 = V::xor3(w2.rotr::<17, 15>(), w2.rotr::<19, 13>(), w2.shr::<10>());
+                w[j] = w[j].add(s0).add(w[(j + 9) % 16]).add(s1);
+            }
+            let s1 = V::xor3(e.rotr::<6, 26>(), e.rotr::<11, 21>(), e.rotr::<25, 7>());
+            let t1 = h
+                .add(s1)
+                .add(V::choose(e, f, g))
+                .add(V::splat(SHA256_K[16 * group + j]))
+                .add(w[j]);
+            let s0 = V::xor3(a.rotr::<2, 30>(), a.rotr::<13, 19>(), a.rotr::<22, 10>());
+            let t2 = s0.add(V::majority(a, b, c));
+            h = g;
+            g = f;
+            f = e;
+            e = d.add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.add(t2);
+        }
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        V::load(word).add(value).store(word);
+    }
+}
+
+/// The SHA-1 compression of one block in each lane. The state is the first 5 words.
+/// The rounds go in groups of 16, as in [`sha256_compress`].
+#[inline(always)]
+unsafe fn sha1_compress<V: Lanes>(
+    state: &mut [[u32; MAX_LANES]; 8],
+    blocks: &[[u8; 64]; MAX_LANES],
+) {
+    let mut w: [V; 16] = std::array::from_fn(|t| V::word(blocks, t));
+    let [mut a, mut b, mut c, mut d, mut e] = std::array::from_fn(|i| V::load(&state[i]));
+    for group in 0..5 {
+        for j in 0..16 {
+            let t = 16 * group + j;
+            if group > 0 {
+                let x = V::xor3(w[(j + 13) % 16], w[(j + 8) % 16], w[(j + 2) % 16]);
+                w[j] = x.xor(w[j]).rotr::<31, 1>();
+            }
+            let (f, k) = match t {
+                0..20 => (V::choose(b, c, d), 0x5A827999),
+                20..40 => (V::xor3(b, c, d), 0x6ED9EBA1),
+                40..60 => (V::majority(b, c, d), 0x8F1BBCDC),
+                _ => (V::xor3(b, c, d), 0xCA62C1D6),
+            };
+            let temp = a.rotr::<27, 5>().add(f).add(e).add(V::splat(k)).add(w[j]);
+            e = d;
+            d = c;
+            c = b.rotr::<2, 30>();
+            b = a;
+            a = temp;
+        }
+    }
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e]) {
+        V::load(word).add(value).store(word);
+    }
+}
+
+/// The address of the word `t` of the first block. A gather reads the word `t` of
+/// each block at an offset in bytes from it. Therefore the pointer comes from the
+/// array of all the blocks, and not from `blocks[0]`, which covers one block only.
+#[cfg(target_arch = "x86_64")]
+fn word_base(blocks: &[[u8; 64]; MAX_LANES], t: usize) -> *const u8 {
+    blocks.as_ptr().cast::<u8>().wrapping_add(4 * t)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    #[derive(Clone, Copy)]
+    struct Avx2(__m256i);
+
+    impl Lanes for Avx2 {
+        const LANES: usize = 8;
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn splat(value: u32) -> Self {
+            Avx2(_mm256_set1_epi32(value as i32))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn load(words: &[u32; MAX_LANES]) -> Self {
+            Avx2(_mm256_loadu_si256(words.as_ptr().cast()))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn word(blocks: &[[u8; 64]; MAX_LANES], t: usize) -> Self {
+            let offsets = _mm256_setr_epi32(0, 64, 128, 192, 256, 320, 384, 448);
+            let words = _mm256_i32gather_epi32::<1>(word_base(blocks, t).cast(), offsets);
+            // Reverses the bytes of each word.
+            let order = _mm256_setr_epi8(
+                3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12, 3, 2, 1, 0, 7, 6, 5, 4, 11,
+                10, 9, 8, 15, 14, 13, 12,
+            );
+            Avx2(_mm256_shuffle_epi8(words, order))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn store(self, words: &mut [u32; MAX_LANES]) {
+            _mm256_storeu_si256(words.as_mut_ptr().cast(), self.0)
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn add(self, other: Self) -> Self {
+            Avx2(_mm256_add_epi32(self.0, other.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn xor(self, other: Self) -> Self {
+            Avx2(_mm256_xor_si256(self.0, other.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn rotr<const R: i32, const L: i32>(self) -> Self {
+            let right = _mm256_srli_epi32::<R>(self.0);
+            Avx2(_mm256_or_si256(right, _mm256_slli_epi32::<L>(self.0)))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn shr<const R: i32>(self) -> Self {
+            Avx2(_mm256_srli_epi32::<R>(self.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn xor3(x: Self, y: Self, z: Self) -> Self {
+            x.xor(y).xor(z)
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn choose(x: Self, y: Self, z: Self) -> Self {
+            // `z ^ (x & (y ^ z))` has one operation less than the definition.
+            Avx2(_mm256_xor_si256(
+                z.0,
+                _mm256_and_si256(x.0, _mm256_xor_si256(y.0, z.0)),
+            ))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        unsafe fn majority(x: Self, y: Self, z: Self) -> Self {
+            // `(x & y) | (z & (x | y))`.
+            let both = _mm256_and_si256(x.0, y.0);
+            let either = _mm256_or_si256(x.0, y.0);
+            Avx2(_mm256_or_si256(both, _mm256_and_si256(z.0, either)))
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Avx512(__m512i);
+
+    impl Lanes for Avx512 {
+        const LANES: usize = 16;
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn splat(value: u32) -> Self {
+            Avx512(_mm512_set1_epi32(value as i32))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn load(words: &[u32; MAX_LANES]) -> Self {
+            Avx512(_mm512_loadu_si512(words.as_ptr().cast()))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn word(blocks: &[[u8; 64]; MAX_LANES], t: usize) -> Self {
+            let offsets = _mm512_setr_epi32(
+                0, 64, 128, 192, 256, 320, 384, 448, 512, 576, 640, 704, 768, 832, 896, 960,
+            );
+            let words = _mm512_i32gather_epi32::<1>(offsets, word_base(blocks, t).cast());
+            // Reverses the bytes of each word with two rotations, because a shuffle of
+            // bytes needs AVX-512BW: bytes 0 and 2 move left, and bytes 1 and 3 right.
+            let left = _mm512_rol_epi32::<8>(words);
+            let right = _mm512_ror_epi32::<8>(words);
+            let mask = _mm512_set1_epi32(0x00FF00FF);
+            Avx512(_mm512_ternarylogic_epi32::<0xAC>(mask, right, left))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn store(self, words: &mut [u32; MAX_LANES]) {
+            _mm512_storeu_si512(words.as_mut_ptr().cast(), self.0)
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn add(self, other: Self) -> Self {
+            Avx512(_mm512_add_epi32(self.0, other.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn xor(self, other: Self) -> Self {
+            Avx512(_mm512_xor_si512(self.0, other.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn rotr<const R: i32, const L: i32>(self) -> Self {
+            Avx512(_mm512_ror_epi32::<R>(self.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn shr<const R: i32>(self) -> Self {
+            Avx512(_mm512_srl_epi32(self.0, _mm_cvtsi32_si128(R)))
+        }
+
+        // AVX-512 computes any function of three inputs in one instruction. The
+        // immediate is the table of the function, for the inputs 0xF0, 0xCC, 0xAA.
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn xor3(x: Self, y: Self, z: Self) -> Self {
+            Avx512(_mm512_ternarylogic_epi32::<0x96>(x.0, y.0, z.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn choose(x: Self, y: Self, z: Self) -> Self {
+            Avx512(_mm512_ternarylogic_epi32::<0xCA>(x.0, y.0, z.0))
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn majority(x: Self, y: Self, z: Self) -> Self {
+            Avx512(_mm512_ternarylogic_epi32::<0xE8>(x.0, y.0, z.0))
+        }
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn sha256_avx2(state: &mut [[u32; MAX_LANES]; 8], blocks: &[[u8; 64]; MAX_LANES]) {
+        sha256_compress::<Avx2>(state, blocks)
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn sha1_avx2(state: &mut [[u32; MAX_LANES]; 8], blocks: &[[u8; 64]; MAX_LANES]) {
+        sha1_compress::<Avx2>(state, blocks)
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn sha256_avx512(state: &mut [[u32; MAX_LANES]; 8], blocks: &[[u8; 64]; MAX_LANES]) {
+        sha256_compress::<Avx512>(state, blocks)
+    }
+
+    #[target_feature(enable = "avx512f")]
+    unsafe fn sha1_avx512(state: &mut [[u32; MAX_LANES]; 8], blocks: &[[u8; 64]; MAX_LANES]) {
+        sha1_compress::<Avx512>(state, blocks)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn lanes_avx2(values: &[&[u8]], sha: Sha, out: &mut [[u8; 32]]) {
+        let compress = match sha {
+            Sha::Sha1 => sha1_avx2,
+            Sha::Sha256 => sha256_avx2,
+        };
+        run_lanes::<Avx2>(values, sha, out, compress)
+    }
+
+    #[target_feature(enable = "avx512f")]
+    pub unsafe fn lanes_avx512(values: &[&[u8]], sha: Sha, out: &mut [[u8; 32]]) {
+        let compress = match sha {
+            Sha::Sha1 => sha1_avx512,
+            Sha::Sha256 => sha256_avx512,
+        };
+        run_lanes::<Avx512>(values, sha, out, compress)
+    }
+}
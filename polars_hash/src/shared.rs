@@ -1,93 +1,1020 @@
 use polars::chunked_array::ops::arity::{unary_elementwise, unary_elementwise_values};
 use polars::prelude::*;
+use pyo3_polars::export::polars_arrow::offset::OffsetsBuffer;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+use crate::affix::{Affix, Affixed};
+use crate::canonical::{canonical_bytes, check_canonical};
+use crate::input_encoding::{check_encoding, encode_strings, InputEncoding, OnDecodeError};
+use crate::row_encode::{
+    check_row_dtype, check_version, for_each_struct_row, ROWS_FOR_ONE_TASK, VERSION,
+};
+// polars-core is not a direct dependency. `row_encode` takes the pool from here too.
+use pyo3_polars::export::polars_core::runtime::RAYON;
+
+/// What a hasher does with a null value.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum NullAs {
+    /// A null value gives a null hash.
+    #[default]
+    Propagate,
+    /// A null value hashes as a value with no bytes.
+    Empty,
+    /// A null value hashes as these bytes.
+    Sentinel(Vec<u8>),
+}
+
+/// The kwargs of the input column, which each hasher takes beside its own.
+///
+/// The expressions flatten this struct into their kwargs. Therefore an option of the
+/// input needs one field here, and every hasher reads it.
+#[derive(Deserialize)]
+pub struct InputKwargs {
+    /// The row encoding of a Struct input. `None` is the version that this build
+    /// writes, and a user who keeps the hashes gives the number.
+    version: Option<u64>,
+    #[serde(default)]
+    null_as: NullAs,
+    #[serde(default)]
+    input_encoding: InputEncoding,
+    #[serde(default)]
+    on_decode_error: OnDecodeError,
+    /// The bytes that the hasher reads before each value.
+    #[serde(default)]
+    prefix: Option<Affix>,
+    /// The bytes that the hasher reads after each value.
+    #[serde(default)]
+    suffix: Option<Affix>,
+}
+
+impl InputKwargs {
+    fn version(&self) -> u64 {
+        self.version.unwrap_or(VERSION)
+    }
+
+    /// The bytes that a null value hashes as, or `None` to keep the null.
+    fn null_bytes(&self) -> Option<&[u8]> {
+        match &self.null_as {
+            NullAs::Propagate => None,
+            NullAs::Empty => Some(&[]),
+            NullAs::Sentinel(bytes) => Some(bytes),
+        }
+    }
+
+    /// Tells if a null value gives a null hash, which is the default of `null_as`.
+    pub fn propagates_nulls(&self) -> bool {
+        matches!(self.null_as, NullAs::Propagate)
+    }
+
+    /// Checks the inputs and these kwargs when polars builds the plan. `fields[0]` is
+    /// the column to hash, and a prefix or a suffix column is another field. Therefore
+    /// a lazy query with a wrong input fails before it reads any data.
+    pub fn check_fields(&self, fields: &[Field]) -> PolarsResult<()> {
+        if let Some(version) = self.version {
+            check_version(version)?;
+        }
+        check_hashed(fields[0].dtype(), self.input_encoding)?;
+        for (affix, name) in [(&self.prefix, "prefix"), (&self.suffix, "suffix")] {
+            if let Some(affix) = affix {
+                affix.check_field(fields, name)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Tells if the input at `index` is the column of a prefix or a suffix.
+    fn reads_column(&self, index: usize) -> bool {
+        [&self.prefix, &self.suffix]
+            .into_iter()
+            .any(|affix| matches!(affix, Some(Affix::Column(i)) if *i == index))
+    }
+
+    /// Reads the columns of a prefix and a suffix from the inputs of the expression.
+    /// Each expression calls this before it hashes.
+    pub fn with_inputs(mut self, inputs: &[Series]) -> PolarsResult<Self> {
+        let column = |affix: Affix, name| {
+            affix
+                .resolve(inputs, name)?
+                .for_elements_of(&inputs[0], name)
+        };
+        self.prefix = self.prefix.map(|a| column(a, "prefix")).transpose()?;
+        self.suffix = self.suffix.map(|a| column(a, "suffix")).transpose()?;
+        Ok(self)
+    }
+
+    /// Tells if the kwargs have a prefix or a suffix.
+    fn has_affixes(&self) -> bool {
+        self.prefix.is_some() || self.suffix.is_some()
+    }
+
+    /// Tells if a prefix or a suffix is a column, with its own bytes on each row. A
+    /// walk then needs the row of each value, and [`for_each_affixed`] gives it.
+    fn has_affix_rows(&self) -> bool {
+        [&self.prefix, &self.suffix]
+            .into_iter()
+            .any(|affix| matches!(affix, Some(Affix::Rows(_))))
+    }
+
+    /// Gives `value` with the prefix and the suffix that are the same on each row. A
+    /// walk calls this only if [`InputKwargs::has_affix_rows`] is false.
+    fn affixed<'a>(&'a self, value: &'a [u8]) -> Affixed<'a> {
+        fn bytes(affix: &Option<Affix>) -> &[u8] {
+            match affix {
+                Some(Affix::Bytes(bytes)) => bytes,
+                _ => &[],
+            }
+        }
+        Affixed {
+            prefix: bytes(&self.prefix),
+            value,
+            suffix: bytes(&self.suffix),
+        }
+    }
+
+    /// Gives `value` with the prefix and the suffix of `row`.
+    fn affixed_at<'a>(&'a self, row: usize, value: &'a [u8]) -> PolarsResult<Affixed<'a>> {
+        let at = |affix: &'a Option<Affix>, name| match affix {
+            Some(affix) => affix.at(row, name),
+            None => Ok(&[][..]),
+        };
+        Ok(Affixed {
+            prefix: at(&self.prefix, "prefix")?,
+            value,
+            suffix: at(&self.suffix, "suffix")?,
+        })
+    }
+}
+
+/// Gives `s` with the text of each String value in `input_encoding`, and with each null
+/// value changed to the bytes of `null_as`. It also checks that a column of prefixes or
+/// suffixes has a row for each value.
+///
+/// The walks below give a null value to `op` as `None` in many places, and a hasher
+/// has a fast walk for each data type. Therefore the bytes go into the column before
+/// the walk, and not into each walk. The result is the Binary column of the bytes that
+/// the walk reads, so the hash of each other value does not change. A column in UTF-8
+/// without nulls, or with `null_as` of "propagate", stays as it is.
+pub fn with_input_bytes<'a>(s: &'a Series, input: &InputKwargs) -> PolarsResult<Cow<'a, Series>> {
+    let out = with_encoding_and_nulls(s, input)?;
+    for (affix, name) in [(&input.prefix, "prefix"), (&input.suffix, "suffix")] {
+        if let Some(affix) = affix {
+            affix.check_len(out.len(), name)?;
+        }
+    }
+    Ok(out)
+}
+
+fn with_encoding_and_nulls<'a>(
+    s: &'a Series,
+    input: &InputKwargs,
+) -> PolarsResult<Cow<'a, Series>> {
+    if input.input_encoding != InputEncoding::Utf8 {
+        let out = encode_strings(
+            s,
+            input.input_encoding,
+            input.on_decode_error,
+            input.null_bytes(),
+        )?;
+        return Ok(Cow::Owned(out.into_series()));
+    }
+    let Some(null_bytes) = input.null_bytes() else {
+        return Ok(Cow::Borrowed(s));
+    };
+    if s.null_count() == 0 {
+        return Ok(Cow::Borrowed(s));
+    }
+    let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
+    for_each_value(s, input, |v| {
+        builder.append_value(v.unwrap_or(null_bytes));
+        Ok(())
+    })?;
+    Ok(Cow::Owned(builder.finish().into_series()))
+}
+
+/// Gives the bytes of each value of `s` to `f`, in order.
+///
+/// A Struct column goes through the row encoder one row at a time, and every other
+/// column reads as [`canonical_bytes`]. The walks below use this where the fast walk
+/// of a String column does not apply.
+pub fn for_each_value<F>(s: &Series, input: &InputKwargs, f: F) -> PolarsResult<()>
+where
+    F: FnMut(Option<&[u8]>) -> PolarsResult<()>,
+{
+    match s.dtype() {
+        DataType::Struct(_) => for_each_struct_row(s, input.version(), f),
+        _ => canonical_bytes(s)?.iter().try_for_each(f),
+    }
+}
+
+/// Gives each value of `s` to `f` with the prefix and the suffix of its row, in order.
+///
+/// `start` is the row of the whole column where `s` starts, as [`in_tasks`] gives it.
+/// A column of prefixes or suffixes has a row for each row of the whole column, and
+/// the task reads its own part of it.
+pub fn for_each_affixed<F>(
+    s: &Series,
+    input: &InputKwargs,
+    start: usize,
+    mut f: F,
+) -> PolarsResult<()>
+where
+    F: FnMut(Option<Affixed<'_>>) -> PolarsResult<()>,
+{
+    let mut row = start;
+    for_each_value(s, input, |v| {
+        let v = v.map(|v| input.affixed_at(row, v)).transpose()?;
+        row += 1;
+        f(v)
+    })
+}
+
+/// Gives the hash of each row of a Categorical or Enum column to `f`, in order.
+///
+/// A category column has many rows and few strings. Therefore `op` runs once for each
+/// category that a row uses, on the bytes of its string, and the other rows of that
+/// category take the hash by their physical index. The bytes are the bytes of the
+/// String column, and therefore so is the hash. A category that no row uses gets no
+/// hash, because one mapping can hold the categories of many columns.
+fn for_each_category<R, F>(s: &Series, mut op: impl FnMut(&[u8]) -> R, mut f: F) -> PolarsResult<()>
+where
+    F: FnMut(Option<&R>),
+{
+    let (DataType::Categorical(_, mapping) | DataType::Enum(_, mapping)) = s.dtype() else {
+        polars_bail!(InvalidOperation: "expected a `Categorical` or `Enum` column, got `{}`", s.dtype())
+    };
+    let ids = s.to_physical_repr().cast(&DataType::UInt32)?;
+    // The key is the physical id. A shared mapping can give a column a few large
+    // ids, and a Vec as large as the largest id would be mostly empty.
+    let mut hashes: PlHashMap<u32, R> = PlHashMap::new();
+    for id in ids.u32()?.iter() {
+        let Some(id) = id else {
+            f(None);
+            continue;
+        };
+        if !hashes.contains_key(&id) {
+            let value = mapping
+                .cat_to_str(id)
+                .ok_or_else(|| polars_err!(ComputeError: "category {} has no string", id))?;
+            hashes.insert(id, op(value.as_bytes()));
+        }
+        f(hashes.get(&id));
+    }
+    Ok(())
+}
+
+/// Runs `op` on the rows of `s` and `seeds`, in tasks of [`ROWS_FOR_ONE_TASK`] rows,
+/// as `encode_rows` does. A hasher reads each row alone, and therefore the tasks give
+/// the hashes of one walk. Each task is a chunk of the result.
+///
+/// `seeds` is split with `s` if it has a value for each row. A column of length 1 is
+/// one value for all the rows, and each task takes it as it is. Columns of other
+/// lengths go to `op` in one task, which gives the error.
+///
+/// `op` also takes the row where its task starts. A walk reads the prefix or the
+/// suffix of a row from that row of their column.
+pub fn in_tasks<T, F>(s: &Series, seeds: Option<&Series>, op: F) -> PolarsResult<ChunkedArray<T>>
+where
+    T: PolarsDataType,
+    F: Fn(&Series, Option<&Series>, usize) -> PolarsResult<ChunkedArray<T>> + Sync,
+{
+    let rows = seeds.map_or(s.len(), |seeds| s.len().max(seeds.len()));
+    let fits = |column: &Series| column.len() == rows || column.len() == 1;
+    if rows <= ROWS_FOR_ONE_TASK || !fits(s) || !seeds.is_none_or(fits) {
+        return op(s, seeds, 0);
+    }
+    tasks_of_range(s, seeds, &op, rows, 0, rows)
+}
+
+/// Runs `op` on the rows from `start` to `end`, and gives the work to more than one
+/// thread if there are sufficient rows. It splits as `row_encode` does, and it is safe
+/// in each engine for the same reason.
+fn tasks_of_range<T, F>(
+    s: &Series,
+    seeds: Option<&Series>,
+    op: &F,
+    rows: usize,
+    start: usize,
+    end: usize,
+) -> PolarsResult<ChunkedArray<T>>
+where
+    T: PolarsDataType,
+    F: Fn(&Series, Option<&Series>, usize) -> PolarsResult<ChunkedArray<T>> + Sync,
+{
+    if end - start <= ROWS_FOR_ONE_TASK {
+        let part = |column: &Series| {
+            if column.len() == rows {
+                column.slice(start as i64, end - start)
+            } else {
+                column.clone()
+            }
+        };
+        return op(&part(s), seeds.map(part).as_ref(), start);
+    }
+
+    let middle = start + (end - start) / 2;
+    let (first, second) = RAYON.join(
+        || tasks_of_range(s, seeds, op, rows, start, middle),
+        || tasks_of_range(s, seeds, op, rows, middle, end),
+    );
+    // Each half is a chunk of the result, and the chunks move without a copy of the
+    // bytes.
+    let (first, second) = (first?, second?);
+    let chunks = first.downcast_iter().chain(second.downcast_iter()).cloned();
+    Ok(ChunkedArray::from_chunk_iter_like(&first, chunks))
+}
 
 /// Runs `op` on the bytes of each value of a String column or a Binary column.
 ///
 /// A hash reads bytes. Therefore the two data types are different only in the method
 /// to read an element. This function keeps that match in one place. Each expression
 /// stays one line, all of them give the same error message, and `encode_rows` can send
-/// its bytes to any of them.
+/// its bytes to any of them. A Struct column reads as its encoded rows, a category
+/// column hashes each category once, and a column of another data type reads as the
+/// bytes that [`canonical_bytes`] gives it.
 ///
 /// A column with nulls uses the walk that omits them. The `_values` walk reads each
 /// slot, and therefore a column of nulls cost as much as a full column. The `_values`
 /// walk is still the faster walk if there are no nulls. Both walks are here.
-pub fn hash_bytes<V, F, R>(s: &Series, op: F) -> PolarsResult<ChunkedArray<V>>
+///
+/// A long column goes to more than one thread, by [`in_tasks`].
+pub fn hash_bytes<V, F, R>(s: &Series, input: &InputKwargs, op: F) -> PolarsResult<ChunkedArray<V>>
+where
+    V: PolarsDataType,
+    F: Fn(&[u8]) -> R + Sync,
+    R: Clone,
+    V::Array: ArrayFromIter<R> + ArrayFromIter<Option<R>>,
+{
+    let s = &*with_input_bytes(s, input)?;
+    let op = |v: Affixed| v.joined(&op);
+    in_tasks(s, None, |s, _, start| {
+        hash_bytes_of_one_task(s, input, start, &op)
+    })
+}
+
+fn hash_bytes_of_one_task<V, F, R>(
+    s: &Series,
+    input: &InputKwargs,
+    start: usize,
+    op: &F,
+) -> PolarsResult<ChunkedArray<V>>
 where
     V: PolarsDataType,
-    F: Fn(&[u8]) -> R,
+    F: Fn(Affixed) -> R,
+    R: Clone,
     V::Array: ArrayFromIter<R> + ArrayFromIter<Option<R>>,
 {
+    let bytes = |v: &[u8]| op(input.affixed(v));
     match (s.dtype(), s.null_count() == 0) {
+        // A row of this walk has its own prefix or suffix, so it cannot take the
+        // hash of a category or another fast walk.
+        _ if input.has_affix_rows() => {
+            let mut out = Vec::with_capacity(s.len());
+            for_each_affixed(s, input, start, |v| {
+                out.push(v.map(op));
+                Ok(())
+            })?;
+            Ok(ChunkedArray::with_chunk(
+                s.name().clone(),
+                V::Array::arr_from_iter(out),
+            ))
+        }
         (DataType::String, true) => Ok(unary_elementwise_values(s.str()?, |v: &str| {
-            op(v.as_bytes())
+            bytes(v.as_bytes())
         })),
         (DataType::String, false) => Ok(unary_elementwise(s.str()?, |v: Option<&str>| {
-            v.map(|v| op(v.as_bytes()))
+            v.map(|v| bytes(v.as_bytes()))
         })),
-        (DataType::Binary, true) => Ok(unary_elementwise_values(s.binary()?, &op)),
+        (DataType::Binary, true) => Ok(unary_elementwise_values(s.binary()?, bytes)),
         (DataType::Binary, false) => Ok(unary_elementwise(s.binary()?, |v: Option<&[u8]>| {
-            v.map(&op)
+            v.map(bytes)
         })),
-        (dtype, _) => polars_bail!(
-            InvalidOperation: "expected `String` or `Binary` input, got `{}`", dtype
-        ),
+        (DataType::Struct(_), _) => {
+            let mut out = Vec::with_capacity(s.len());
+            for_each_value(s, input, |v| {
+                out.push(v.map(bytes));
+                Ok(())
+            })?;
+            Ok(ChunkedArray::with_chunk(
+                s.name().clone(),
+                V::Array::arr_from_iter(out),
+            ))
+        }
+        (DataType::Categorical(_, _) | DataType::Enum(_, _), _) => {
+            let mut out = Vec::with_capacity(s.len());
+            for_each_category(s, bytes, |h| out.push(h.cloned()))?;
+            Ok(ChunkedArray::with_chunk(
+                s.name().clone(),
+                V::Array::arr_from_iter(out),
+            ))
+        }
+        _ => hash_bytes_of_one_task(&canonical_bytes(s)?.into_series(), input, start, op),
     }
 }
 
 /// The equivalent of [`hash_bytes`] for a digest in hexadecimal.
 ///
 /// `op` writes to a buffer that each row uses again. It does not return a `String`.
-/// Therefore a digest needs no memory of its own.
-pub fn hash_bytes_into_string<F>(s: &Series, mut op: F) -> PolarsResult<StringChunked>
+/// Therefore a digest needs no memory of its own. A digest reads the prefix, the
+/// value and the suffix as three parts, and they need no buffer either.
+pub fn hash_bytes_into_string<F>(
+    s: &Series,
+    input: &InputKwargs,
+    op: F,
+) -> PolarsResult<StringChunked>
+where
+    F: Fn(Affixed, &mut std::string::String) + Sync,
+{
+    let s = &*with_input_bytes(s, input)?;
+    in_tasks(s, None, |s, _, start| {
+        hash_bytes_into_string_of_one_task(s, input, start, &op)
+    })
+}
+
+fn hash_bytes_into_string_of_one_task<F>(
+    s: &Series,
+    input: &InputKwargs,
+    start: usize,
+    op: &F,
+) -> PolarsResult<StringChunked>
 where
-    F: FnMut(&[u8], &mut std::string::String),
+    F: Fn(Affixed, &mut std::string::String),
 {
+    let bytes = |v: &[u8], out: &mut std::string::String| op(input.affixed(v), out);
     match s.dtype() {
+        _ if input.has_affix_rows() => {
+            let mut builder = StringChunkedBuilder::new(s.name().clone(), s.len());
+            let mut digest = std::string::String::new();
+            for_each_affixed(s, input, start, |v| {
+                match v {
+                    Some(v) => {
+                        digest.clear();
+                        op(v, &mut digest);
+                        builder.append_value(&digest);
+                    }
+                    None => builder.append_null(),
+                }
+                Ok(())
+            })?;
+            Ok(builder.finish())
+        }
         DataType::String => Ok(s
             .str()?
-            .apply_into_string_amortized(|v, out| op(v.as_bytes(), out))),
-        DataType::Binary => Ok(s.binary()?.apply_into_string_amortized(op)),
-        dtype => polars_bail!(
-            InvalidOperation: "expected `String` or `Binary` input, got `{}`", dtype
-        ),
+            .apply_into_string_amortized(|v, out| bytes(v.as_bytes(), out))),
+        DataType::Struct(_) => {
+            let mut builder = StringChunkedBuilder::new(s.name().clone(), s.len());
+            let mut digest = std::string::String::new();
+            for_each_value(s, input, |v| {
+                match v {
+                    Some(v) => {
+                        digest.clear();
+                        bytes(v, &mut digest);
+                        builder.append_value(&digest);
+                    }
+                    None => builder.append_null(),
+                }
+                Ok(())
+            })?;
+            Ok(builder.finish())
+        }
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            let mut builder = StringChunkedBuilder::new(s.name().clone(), s.len());
+            let digest = |v: &[u8]| {
+                let mut digest = std::string::String::new();
+                bytes(v, &mut digest);
+                digest
+            };
+            for_each_category(s, digest, |h| match h {
+                Some(h) => builder.append_value(h),
+                None => builder.append_null(),
+            })?;
+            Ok(builder.finish())
+        }
+        _ => Ok(canonical_bytes(s)?.apply_into_string_amortized(bytes)),
     }
 }
 
+/// The equivalent of [`hash_bytes_into_string`] for a hasher that is faster on many
+/// values at one time, as the SHA lanes are.
+///
+/// `batch` takes the values of one task as one Binary column, and gives their digests.
+/// A lane reads a value as one slice, and cannot read a prefix and a suffix beside it.
+/// Therefore a call with an affix takes the walk of [`hash_bytes_into_string`], which
+/// gives the parts to `op`. A Categorical or an Enum column has few values, and `op`
+/// hashes each category once as before.
+pub fn hash_batches_into_string<F, B>(
+    s: &Series,
+    input: &InputKwargs,
+    op: F,
+    batch: B,
+) -> PolarsResult<StringChunked>
+where
+    F: Fn(Affixed, &mut std::string::String) + Sync,
+    B: Fn(&BinaryChunked) -> StringChunked + Sync,
+{
+    if input.has_affixes() {
+        return hash_bytes_into_string(s, input, op);
+    }
+    let s = &*with_input_bytes(s, input)?;
+    in_tasks(s, None, |s, _, start| {
+        let out = match s.dtype() {
+            DataType::String => batch(&s.str()?.as_binary()),
+            DataType::Binary => batch(s.binary()?),
+            DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+                return hash_bytes_into_string_of_one_task(s, input, start, &op);
+            }
+            DataType::Struct(_) => {
+                let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
+                for_each_value(s, input, |v| {
+                    builder.append_option(v);
+                    Ok(())
+                })?;
+                batch(&builder.finish())
+            }
+            _ => batch(&canonical_bytes(s)?),
+        };
+        Ok(out.with_name(s.name().clone()))
+    })
+}
+
 /// The equivalent of [`hash_bytes`] for a digest of a set number of bytes.
 ///
 /// [`hash_bytes`] cannot make a `BinaryChunked` from an array of bytes. It collects
 /// what `ArrayFromIter` accepts, and that trait reads a slice or a `Vec`. An array
 /// therefore needs a `Vec` of its own for each row. A builder takes the array as a
 /// slice instead, and writes it straight into the one buffer of the column.
-pub fn hash_bytes_into_binary<const N: usize, F>(s: &Series, op: F) -> PolarsResult<BinaryChunked>
+pub fn hash_bytes_into_binary<const N: usize, F>(
+    s: &Series,
+    input: &InputKwargs,
+    op: F,
+) -> PolarsResult<BinaryChunked>
 where
-    F: Fn(&[u8]) -> [u8; N],
+    F: Fn(&[u8]) -> [u8; N] + Sync,
+{
+    let s = &*with_input_bytes(s, input)?;
+    let op = |v: Affixed| v.joined(&op);
+    in_tasks(s, None, |s, _, start| {
+        hash_bytes_into_binary_of_one_task(s, input, start, &op)
+    })
+}
+
+fn hash_bytes_into_binary_of_one_task<const N: usize, F>(
+    s: &Series,
+    input: &InputKwargs,
+    start: usize,
+    op: &F,
+) -> PolarsResult<BinaryChunked>
+where
+    F: Fn(Affixed) -> [u8; N],
 {
     let mut builder = BinaryChunkedBuilder::new(s.name().clone(), s.len());
-    match s.dtype() {
-        DataType::String => {
-            for value in s.str()?.iter() {
-                match value {
-                    Some(value) => builder.append_value(op(value.as_bytes())),
-                    None => builder.append_null(),
-                }
+    if input.has_affix_rows() {
+        for_each_affixed(s, input, start, |v| {
+            match v {
+                Some(v) => builder.append_value(op(v)),
+                None => builder.append_null(),
             }
+            Ok(())
+        })?;
+        return Ok(builder.finish());
+    }
+    let bytes = |v: &[u8]| op(input.affixed(v));
+    if let DataType::Categorical(_, _) | DataType::Enum(_, _) = s.dtype() {
+        for_each_category(s, bytes, |h| match h {
+            Some(h) => builder.append_value(h),
+            None => builder.append_null(),
+        })?;
+        return Ok(builder.finish());
+    }
+    for_each_value(s, input, |v| {
+        match v {
+            Some(v) => builder.append_value(bytes(v)),
+            None => builder.append_null(),
         }
-        DataType::Binary => {
-            for value in s.binary()?.iter() {
-                match value {
-                    Some(value) => builder.append_value(op(value)),
-                    None => builder.append_null(),
-                }
-            }
+        Ok(())
+    })?;
+    Ok(builder.finish())
+}
+
+/// Gives the bytes of each value of `s` to `f`, with the seed, the prefix and the
+/// suffix of its row. `start` is the row where the task starts, as in
+/// [`for_each_affixed`].
+///
+/// A value column of length 1 broadcasts over the seeds, as the timestamp of
+/// `thash_encode` does. A seed column of length 1 never reaches here: the callers take
+/// it as a scalar, which is the walk of the kwarg and the fast one.
+fn for_each_seeded<T, F>(
+    s: &Series,
+    input: &InputKwargs,
+    seeds: &Series,
+    start: usize,
+    mut f: F,
+) -> PolarsResult<()>
+where
+    T: PolarsIntegerType,
+    F: FnMut(Option<Affixed<'_>>, Option<T::Native>) -> PolarsResult<()>,
+{
+    let seeds = seed_arg::<T>(seeds)?;
+    let mut row = start;
+    let mut f = |v: Option<&[u8]>, seed| {
+        let v = v.map(|v| input.affixed_at(row, v)).transpose()?;
+        row += 1;
+        f(v, seed)
+    };
+    match (s.len(), seeds.len()) {
+        (1, _) => {
+            let mut value = None;
+            for_each_value(s, input, |v| {
+                value = v.map(<[u8]>::to_vec);
+                Ok(())
+            })?;
+            seeds.iter().try_for_each(|seed| f(value.as_deref(), seed))
         }
-        dtype => polars_bail!(
-            InvalidOperation: "expected `String` or `Binary` input, got `{}`", dtype
+        (len, seed_len) if len == seed_len => {
+            let mut seeds = seeds.iter();
+            for_each_value(s, input, |v| f(v, seeds.next().flatten()))
+        }
+        (len, seed_len) => polars_bail!(
+            ShapeMismatch:
+            "value column has length {} and seed has length {}, expected equal lengths or a scalar",
+            len, seed_len
         ),
     }
+}
+
+/// Coerce a seed column to the seed width of its hasher. The cast is strict, so a
+/// seed that does not fit raises rather than wrap into a different seed.
+fn seed_arg<T: PolarsIntegerType>(s: &Series) -> PolarsResult<ChunkedArray<T>> {
+    let target = T::get_static_dtype();
+    if !s.dtype().is_integer() {
+        polars_bail!(InvalidOperation: "Seed input needs to be integer, got {}", s.dtype())
+    }
+    let cast = s.strict_cast(&target).map_err(|_| {
+        let e = polars_err!(
+            InvalidOperation: "Seed input needs to fit in {}, got a value outside it", target
+        );
+        // Only the error path looks for the row. The cast that is not strict gives a
+        // null where the value does not fit.
+        match s
+            .cast(&target)
+            .map(|cast| first_true(&(s.is_not_null() & cast.is_null())))
+        {
+            Ok(Some(row)) => at_row(e, row, s.name()),
+            _ => e,
+        }
+    })?;
+    Ok(cast.unpack::<T>()?.clone())
+}
+
+/// Gives the first row that is `true`.
+fn first_true(mask: &BooleanChunked) -> Option<usize> {
+    mask.iter().position(|b| b == Some(true))
+}
+
+/// Checks for a null seed beside a value before the walk. The walk is in tasks, and a
+/// task does not know the row where it starts, so it cannot tell the row. A seed
+/// column without nulls has a `null_count` of 0, and it costs nothing here.
+fn check_null_seeds(s: &Series, seeds: &Series) -> PolarsResult<()> {
+    if seeds.null_count() == 0 || s.null_count() == s.len() {
+        return Ok(());
+    }
+    let null_seeds = seeds.is_null();
+    let row = match s.len() {
+        1 => first_true(&null_seeds),
+        len if len == seeds.len() => first_true(&(s.is_not_null() & null_seeds)),
+        // `for_each_seeded` gives the error of the lengths.
+        _ => None,
+    };
+    match row {
+        Some(row) => Err(at_row(
+            polars_err!(ComputeError: "Seed may not be null"),
+            row,
+            seeds.name(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Gives the one seed of a column of length 1. A null seed is an error, as a null
+/// precision is in `thash_encode`: a seed of 0 is a seed like any other, so a null has
+/// no seed to fall back to.
+fn scalar_seed<T: PolarsIntegerType>(seeds: &Series) -> PolarsResult<T::Native> {
+    seed_arg::<T>(seeds)?
+        .get(0)
+        .ok_or_else(|| polars_err!(ComputeError: "Seed may not be null"))
+}
+
+/// The equivalent of [`hash_bytes`] for a seeded hasher.
+///
+/// `seeds` is the seed column, if the caller gave an expression, and `seed` is the
+/// kwarg otherwise. A seed column of length 1 is one seed for the whole column, and
+/// it takes the walk of [`hash_bytes`] like the kwarg does. A longer one gives a seed
+/// to each row. A null value gives a null, whatever its seed, and a null seed beside a
+/// value is an error.
+pub fn hash_bytes_seeded<V, T, F, R>(
+    s: &Series,
+    input: &InputKwargs,
+    seeds: Option<&Series>,
+    seed: T::Native,
+    op: F,
+) -> PolarsResult<ChunkedArray<V>>
+where
+    V: PolarsDataType,
+    T: PolarsIntegerType,
+    F: Fn(&[u8], T::Native) -> R + Sync,
+    R: Clone,
+    V::Array: ArrayFromIter<R> + ArrayFromIter<Option<R>>,
+{
+    let seeds = match seeds {
+        None => return hash_bytes(s, input, |v| op(v, seed)),
+        Some(seeds) if seeds.len() == 1 => {
+            let seed = scalar_seed::<T>(seeds)?;
+            return hash_bytes(s, input, |v| op(v, seed));
+        }
+        Some(seeds) => seeds,
+    };
+    let s = &*with_input_bytes(s, input)?;
+    check_null_seeds(s, seeds)?;
+    let op = |v: Affixed, seed| v.joined(|v| op(v, seed));
+    in_tasks(s, Some(seeds), |s, seeds, start| {
+        let seeds = seeds.expect("the tasks keep the seed column");
+        hash_bytes_seeded_of_one_task::<V, T, _, R>(s, input, seeds, start, &op)
+    })
+}
+
+fn hash_bytes_seeded_of_one_task<V, T, F, R>(
+    s: &Series,
+    input: &InputKwargs,
+    seeds: &Series,
+    start: usize,
+    op: &F,
+) -> PolarsResult<ChunkedArray<V>>
+where
+    V: PolarsDataType,
+    T: PolarsIntegerType,
+    F: Fn(Affixed, T::Native) -> R,
+    V::Array: ArrayFromIter<R> + ArrayFromIter<Option<R>>,
+{
+    let mut out = Vec::with_capacity(seeds.len());
+    for_each_seeded::<T, _>(s, input, seeds, start, |v, seed| {
+        out.push(match (v, seed) {
+            (Some(v), Some(seed)) => Some(op(v, seed)),
+            (Some(_), None) => polars_bail!(ComputeError: "Seed may not be null"),
+            (None, _) => None,
+        });
+        Ok(())
+    })?;
+    Ok(ChunkedArray::with_chunk(
+        s.name().clone(),
+        V::Array::arr_from_iter(out),
+    ))
+}
+
+/// The equivalent of [`hash_bytes_into_binary`] for a seeded hasher, by the rules of
+/// [`hash_bytes_seeded`].
+pub fn hash_bytes_seeded_into_binary<const N: usize, T, F>(
+    s: &Series,
+    input: &InputKwargs,
+    seeds: Option<&Series>,
+    seed: T::Native,
+    op: F,
+) -> PolarsResult<BinaryChunked>
+where
+    T: PolarsIntegerType,
+    F: Fn(&[u8], T::Native) -> [u8; N] + Sync,
+{
+    let seeds = match seeds {
+        None => return hash_bytes_into_binary(s, input, |v| op(v, seed)),
+        Some(seeds) if seeds.len() == 1 => {
+            let seed = scalar_seed::<T>(seeds)?;
+            return hash_bytes_into_binary(s, input, |v| op(v, seed));
+        }
+        Some(seeds) => seeds,
+    };
+    let s = &*with_input_bytes(s, input)?;
+    check_null_seeds(s, seeds)?;
+    let op = |v: Affixed, seed| v.joined(|v| op(v, seed));
+    in_tasks(s, Some(seeds), |s, seeds, start| {
+        let seeds = seeds.expect("the tasks keep the seed column");
+        hash_bytes_seeded_into_binary_of_one_task::<N, T, _>(s, input, seeds, start, &op)
+    })
+}
+
+fn hash_bytes_seeded_into_binary_of_one_task<const N: usize, T, F>(
+    s: &Series,
+    input: &InputKwargs,
+    seeds: &Series,
+    start: usize,
+    op: &F,
+) -> PolarsResult<BinaryChunked>
+where
+    T: PolarsIntegerType,
+    F: Fn(Affixed, T::Native) -> [u8; N],
+{
+    let mut builder = BinaryChunkedBuilder::new(s.name().clone(), seeds.len());
+    for_each_seeded::<T, _>(s, input, seeds, start, |v, seed| {
+        match (v, seed) {
+            (Some(v), Some(seed)) => builder.append_value(op(v, seed)),
+            (Some(_), None) => polars_bail!(ComputeError: "Seed may not be null"),
+            (None, _) => builder.append_null(),
+        }
+        Ok(())
+    })?;
     Ok(builder.finish())
 }
 
+/// Runs `op` on the elements of a List or an Array column, and gives a List of the
+/// same shape. A column of another data type goes to `op` as it is.
+///
+/// A hasher reads the elements, and not the list. This function walks the offsets as
+/// `row_encode` does, and gives all the elements to `op` in one call. It then puts
+/// the outer offsets and nulls back on the result. Therefore an empty list stays
+/// empty, a null list stays null, and a null element stays a null element. An Array
+/// has the offsets of a List after a cast, and a List inside a List walks again.
+///
+/// `aligned` is a second input with one value for each row of `s`, such as a seed
+/// column. A list row holds more than one element, and each element takes the value
+/// of its row. A length of 1 is one value for all the rows, and stays as it is.
+pub fn hash_elements<F>(s: &Series, aligned: Option<&Series>, op: F) -> PolarsResult<Series>
+where
+    F: Fn(&Series, Option<&Series>) -> PolarsResult<Series>,
+{
+    let Some(Elements {
+        list,
+        values,
+        offsets,
+    }) = elements(s)?
+    else {
+        return op(s, aligned);
+    };
+
+    let aligned = match aligned {
+        Some(aligned) if aligned.len() == s.len() && s.len() != 1 => {
+            Some(aligned.take_slice(&rows_of_offsets(&offsets))?)
+        }
+        aligned => aligned.cloned(),
+    };
+
+    let hashed = hash_elements(&values, aligned.as_ref(), op)?.rechunk();
+    let hashed = hashed.chunks()[0].clone();
+    let arr = list
+        .downcast_get(0)
+        .expect("a rechunked column has one chunk");
+    let out = LargeListArray::new(
+        LargeListArray::default_datatype(hashed.dtype().clone()),
+        OffsetsBuffer::try_from(offsets)?,
+        hashed,
+        arr.validity().cloned(),
+    );
+    Ok(ListChunked::with_chunk(s.name().clone(), out).into_series())
+}
+
+/// The elements of a List column, as [`hash_elements`] reads them.
+struct Elements {
+    /// The column, in one chunk.
+    list: ListChunked,
+    /// The elements of the rows of the column, and no other.
+    values: Series,
+    /// The offset of each row in `values`.
+    offsets: Vec<i64>,
+}
+
+/// Gives the elements of a List or an Array column, or `None` for another column.
+fn elements(s: &Series) -> PolarsResult<Option<Elements>> {
+    let s = match s.dtype() {
+        DataType::List(_) => s.rechunk(),
+        DataType::Array(inner, _) => s.cast(&DataType::List(inner.clone()))?.rechunk(),
+        _ => return Ok(None),
+    };
+    let list = s.list()?.clone();
+    let arr = list
+        .downcast_get(0)
+        .expect("a rechunked column has one chunk");
+    // The offsets apply to the slice, but `get_inner` gives the full values buffer of
+    // the source column. Therefore a slice of ten rows does not hash the rows before it.
+    let (first, last) = (*arr.offsets().first(), *arr.offsets().last());
+    let values = list.get_inner().slice(first, (last - first) as usize);
+    let offsets = arr.offsets().iter().map(|o| o - first).collect();
+    Ok(Some(Elements {
+        list,
+        values,
+        offsets,
+    }))
+}
+
+/// The row of each element of a List, from its offsets.
+fn rows_of_offsets(offsets: &[i64]) -> Vec<IdxSize> {
+    offsets
+        .windows(2)
+        .enumerate()
+        .flat_map(|(row, w)| std::iter::repeat_n(row as IdxSize, (w[1] - w[0]) as usize))
+        .collect()
+}
+
+/// Gives the row of each value that [`hash_elements`] gives to its `op`, or `None` if
+/// `s` is not a List or an Array. A List inside a List walks again, and each element
+/// of an inner list takes the row of the outer list.
+pub fn element_rows(s: &Series) -> PolarsResult<Option<Vec<IdxSize>>> {
+    let Some(Elements {
+        values, offsets, ..
+    }) = elements(s)?
+    else {
+        return Ok(None);
+    };
+    let rows = rows_of_offsets(&offsets);
+    Ok(Some(match element_rows(&values)? {
+        Some(inner) => inner.iter().map(|&i| rows[i as usize]).collect(),
+        None => rows,
+    }))
+}
+
+/// Checks that the walks above read a column of `dtype`. A List or an Array is its
+/// elements, as in [`hash_elements`], and a Struct is its rows, as in
+/// [`for_each_value`].
+fn check_hashed(dtype: &DataType, encoding: InputEncoding) -> PolarsResult<()> {
+    match dtype {
+        DataType::List(inner) | DataType::Array(inner, _) => check_hashed(inner, encoding),
+        DataType::Struct(_) => {
+            check_encoding(encoding, dtype)?;
+            check_row_dtype(dtype)
+        }
+        dtype => {
+            check_encoding(encoding, dtype)?;
+            check_canonical(dtype)
+        }
+    }
+}
+
+/// Checks the seed column of a seeded hasher, when polars builds the plan. The seed
+/// column is the second input if there is one, and the inputs after it are the
+/// columns of [`InputKwargs`]. An unseeded hasher has no seed column, and its second
+/// input is the column of a prefix or a suffix.
+pub fn check_seed_field(fields: &[Field], input: &InputKwargs) -> PolarsResult<()> {
+    match fields.get(1) {
+        Some(seed)
+            if !input.reads_column(1)
+                && !seed.dtype().is_integer()
+                && !seed.dtype().is_unknown() =>
+        {
+            polars_bail!(InvalidOperation: "Seed input needs to be integer, got {}", seed.dtype())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Gives the data type of [`hash_elements`] for an input of data type `input` and a
+/// hasher that writes `dtype`.
+pub fn hashed_dtype(input: &DataType, dtype: DataType) -> DataType {
+    match input {
+        DataType::List(inner) | DataType::Array(inner, _) => {
+            DataType::List(Box::new(hashed_dtype(inner, dtype)))
+        }
+        _ => dtype,
+    }
+}
+
+/// Gives the signed integer type of the width of `dtype`. A hasher writes this type if
+/// the caller asks for a signed output.
+pub fn signed_dtype(dtype: DataType) -> DataType {
+    match dtype {
+        DataType::UInt32 => DataType::Int32,
+        DataType::UInt64 => DataType::Int64,
+        DataType::UInt128 => DataType::Int128,
+        dtype => dtype,
+    }
+}
+
+/// Gives the hashes in `s` as signed integers, if `signed` is true.
+///
+/// Postgres, Java and BigQuery have no unsigned 64-bit integer. A cast of a hash above
+/// `i64::MAX` fails or gives null, and a hash is a set of bits, not a quantity.
+/// Therefore each value keeps its bits, and a hash with the top bit set becomes
+/// negative in two's complement. This is what `(long) h` does in Java.
+pub fn with_sign(s: Series, signed: bool) -> PolarsResult<Series> {
+    if !signed {
+        return Ok(s);
+    }
+    let dtype = signed_dtype(s.dtype().clone());
+    reinterpret(&s, &dtype)
+}
+
+/// Writes `bytes` to `output` as lowercase hexadecimal text.
+///
+/// A digest has few bytes, and a row has little work. Therefore the `String` of
+/// `hex::encode` and the formatter of `write!` cost as much as the hash of a short
+/// value. This function writes through a buffer on the stack, 64 bytes at a time, and
+/// needs no memory of its own.
+pub fn write_hex(bytes: &[u8], output: &mut std::string::String) {
+    let mut buffer = [0u8; 128];
+    output.reserve(bytes.len() * 2);
+    for chunk in bytes.chunks(64) {
+        let hex = &mut buffer[..chunk.len() * 2];
+        hex::encode_to_slice(chunk, hex).expect("the buffer has two bytes for each byte");
+        output.push_str(std::str::from_utf8(hex).expect("hexadecimal text is ASCII"));
+    }
+}
+
 /// Coerce an integer argument to Int64. `_length_expr` already casts on the Python
 /// side, so this only bites callers using `register_plugin_function` directly -- but
 /// the three encoders used to disagree about which widths they accepted.
@@ -109,6 +1036,45 @@ pub fn float_arg(s: &Series, label: &str) -> PolarsResult<Series> {
     }
 }
 
+/// The plan-time form of `str()` on an input. A column of another data type fails
+/// when polars builds the plan, with the name of the input.
+pub fn check_string_field(field: &Field, label: &str) -> PolarsResult<()> {
+    match field.dtype() {
+        DataType::String | DataType::Unknown(_) => Ok(()),
+        dtype => {
+            polars_bail!(InvalidOperation: "{} input needs to be String, got {}", label, dtype)
+        }
+    }
+}
+
+/// Checks the Struct of coordinates that the geohash and H3 encoders read, when polars
+/// builds the plan. A missing field would otherwise fail at the first batch.
+pub fn check_coords_field(field: &Field) -> PolarsResult<()> {
+    let fields = match field.dtype() {
+        DataType::Struct(fields) => fields,
+        DataType::Unknown(_) => return Ok(()),
+        _ => polars_bail!(
+            InvalidOperation:
+            "expected a `Struct` input with the fields `latitude` and `longitude`, got `{}`",
+            field.dtype()
+        ),
+    };
+    for (name, label) in [("latitude", "Latitude"), ("longitude", "Longitude")] {
+        match fields.iter().find(|f| f.name() == name).map(|f| f.dtype()) {
+            Some(DataType::Float32 | DataType::Float64 | DataType::Unknown(_)) => {}
+            Some(dtype) => {
+                polars_bail!(InvalidOperation: "{} input needs to be float, got {}", label, dtype)
+            }
+            None => polars_bail!(
+                InvalidOperation:
+                "expected a `Struct` input with the fields `latitude` and `longitude`, got `{}`",
+                field.dtype()
+            ),
+        }
+    }
+    Ok(())
+}
+
 /// A length-1 argument broadcasts over the other operands; anything else is per-row.
 /// The `unsafe` index is sound only under that length check, so both live here once
 /// rather than being restated at every call site.
@@ -122,22 +1088,67 @@ pub fn scalar_arg(ca: &Int64Chunked, label: &str) -> PolarsResult<Option<i64>> {
     }
 }
 
+/// Adds the row and the name of the column to the error of a value that fails. Only
+/// the error path builds the message. Therefore a walk without errors pays for the
+/// count of its rows, and for nothing more.
+pub fn at_row(e: PolarsError, row: usize, name: &str) -> PolarsError {
+    e.wrap_msg(|msg| format!("{msg}, at row {row} of column {name:?}"))
+}
+
+/// Counts the rows of a walk, for the closure of a `try_*_elementwise` that gives no
+/// index. Polars calls that closure once for each row, in order, and a null row too.
+pub struct Rows<'a> {
+    name: &'a str,
+    row: usize,
+}
+
+impl<'a> Rows<'a> {
+    pub fn of(name: &'a str) -> Self {
+        Rows { name, row: 0 }
+    }
+
+    /// Gives the result of the next row, with the row in its error. See [`at_row`].
+    pub fn next<T>(&mut self, result: PolarsResult<T>) -> PolarsResult<T> {
+        let row = self.row;
+        self.row += 1;
+        result.map_err(|e| at_row(e, row, self.name))
+    }
+}
+
+/// Gives `None` for a row that fails, if the caller gave `strict=False`. Polars does
+/// the same in `cast(strict=False)` and `str.to_date(strict=False)`. A nightly job
+/// with one bad row then keeps the other rows, and the bad row is a null to find.
+pub fn or_null<T>(result: PolarsResult<T>, strict: bool) -> PolarsResult<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(_) if !strict => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 /// Fan a string column out into a struct of string fields, one call to `f` per row.
 /// The null row appends a null to every builder: correctness needs exactly one append
 /// per builder per row, and keeping that in one place is the point -- a missed append
-/// silently misaligns the whole column rather than failing.
+/// silently misaligns the whole column rather than failing. A row that `f` rejects is
+/// a null row too, unless `strict` raises its error.
 pub fn string_struct<const N: usize>(
     ca: &StringChunked,
     names: [&str; N],
+    strict: bool,
     f: impl Fn(&str) -> PolarsResult<[Option<String>; N]>,
 ) -> PolarsResult<StructChunked> {
     let mut builders: [StringChunkedBuilder; N] =
         std::array::from_fn(|i| StringChunkedBuilder::new(names[i].into(), ca.len()));
 
-    for value in ca.iter() {
-        match value {
-            Some(value) => {
-                for (builder, field) in builders.iter_mut().zip(f(value)?) {
+    for (row, value) in ca.iter().enumerate() {
+        let fields = value.map(|value| f(value).map_err(|e| at_row(e, row, ca.name())));
+        match fields
+            .map(|fields| or_null(fields, strict))
+            .transpose()?
+            .flatten()
+        {
+            Some(fields) => {
+                for (builder, field) in builders.iter_mut().zip(fields) {
                     match field {
                         Some(field) => builder.append_value(field),
                         None => builder.append_null(),
@@ -0,0 +1,311 @@
+//! The `hash` and `xxhash64` functions of Spark SQL, over the columns of a row.
+//!
+//! Spark hashes the first column with the seed 42. The hash of each column is then the
+//! seed of the next column, and a null column is skipped. Each value is hashed as the
+//! bytes of its Spark type, and not as the bytes of [`crate::row_encode`]: an `int`
+//! is 4 bytes, a `bigint` is 8 bytes, and a string is its UTF-8 bytes. Therefore the
+//! same value of an `Int32` and of an `Int64` column gives two different hashes, as it
+//! does in Spark.
+//!
+//! `hash` is Murmur3 x86 32, but Spark hashes the bytes after the last 4-byte word in
+//! its own way. `xxhash64` is the reference XXH64.
+
+use crate::row_encode::to_micros;
+use polars::prelude::*;
+use xxhash_rust::xxh64::xxh64;
+
+/// The seed of `hash` and `xxhash64` in Spark SQL.
+const SEED: u64 = 42;
+
+/// `Decimal.MAX_LONG_DIGITS` of Spark. A decimal of this precision or less keeps its
+/// unscaled value in a `long`, and Spark hashes that `long`.
+const MAX_LONG_DIGITS: usize = 18;
+
+/// A hash function of Spark SQL.
+#[derive(Clone, Copy)]
+pub enum Function {
+    /// `hash`, which gives an `int`.
+    Murmur3,
+    /// `xxhash64`, which gives a `long`.
+    XxHash64,
+}
+
+fn mix_k1(k1: u32) -> u32 {
+    k1.wrapping_mul(0xcc9e_2d51)
+        .rotate_left(15)
+        .wrapping_mul(0x1b87_3593)
+}
+
+fn mix_h1(h1: u32, k1: u32) -> u32 {
+    (h1 ^ k1)
+        .rotate_left(13)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64)
+}
+
+fn fmix(mut h1: u32, len: u32) -> u32 {
+    h1 ^= len;
+    h1 ^= h1 >> 16;
+    h1 = h1.wrapping_mul(0x85eb_ca6b);
+    h1 ^= h1 >> 13;
+    h1 = h1.wrapping_mul(0xc2b2_ae35);
+    h1 ^ (h1 >> 16)
+}
+
+/// `Murmur3_x86_32.hashUnsafeBytes` of Spark. The reference MurmurHash3 puts the last
+/// bytes into one word. Spark mixes each of them as a word of its own, and it reads
+/// each one as a signed `byte`.
+fn murmur3_bytes(value: &[u8], seed: u32) -> u32 {
+    let mut words = value.chunks_exact(4);
+    let mut h1 = seed;
+    for word in &mut words {
+        h1 = mix_h1(h1, mix_k1(u32::from_le_bytes(word.try_into().unwrap())));
+    }
+    for &byte in words.remainder() {
+        h1 = mix_h1(h1, mix_k1(byte as i8 as i32 as u32));
+    }
+    fmix(h1, value.len() as u32)
+}
+
+impl Function {
+    /// `hashInt`. Both functions hash the 4 bytes of the `int`, least significant byte
+    /// first.
+    fn int(self, value: i32, seed: u64) -> u64 {
+        match self {
+            Function::Murmur3 => fmix(mix_h1(seed as u32, mix_k1(value as u32)), 4) as u64,
+            Function::XxHash64 => xxh64(&value.to_le_bytes(), seed),
+        }
+    }
+
+    /// `hashLong`, which hashes the 8 bytes of the `long`.
+    fn long(self, value: i64, seed: u64) -> u64 {
+        match self {
+            Function::Murmur3 => {
+                let h1 = mix_h1(seed as u32, mix_k1(value as u32));
+                fmix(mix_h1(h1, mix_k1((value >> 32) as u32)), 8) as u64
+            }
+            Function::XxHash64 => xxh64(&value.to_le_bytes(), seed),
+        }
+    }
+
+    /// `hashUnsafeBytes`.
+    fn bytes(self, value: &[u8], seed: u64) -> u64 {
+        match self {
+            Function::Murmur3 => murmur3_bytes(value, seed as u32) as u64,
+            Function::XxHash64 => xxh64(value, seed),
+        }
+    }
+}
+
+/// The bytes of `BigInteger.toByteArray`: two's complement, most significant byte
+/// first, with the fewest bytes that keep the sign.
+pub fn big_integer_bytes(value: i128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let sign = if value < 0 { 0xff } else { 0x00 };
+    let start = (0..bytes.len() - 1)
+        .find(|&i| bytes[i] != sign || (bytes[i + 1] & 0x80) != (sign & 0x80))
+        .unwrap_or(bytes.len() - 1);
+    bytes[start..].to_vec()
+}
+
+/// `floatToIntBits`, after Spark changes `-0.0` to `0.0`. Java gives one NaN for all
+/// of them, and `f32::NAN` has the same bits as that NaN.
+fn float_bits(value: f32) -> i32 {
+    if value == 0.0 {
+        0
+    } else if value.is_nan() {
+        f32::NAN.to_bits() as i32
+    } else {
+        value.to_bits() as i32
+    }
+}
+
+/// `doubleToLongBits`, after the same changes as [`float_bits`].
+fn double_bits(value: f64) -> i64 {
+    if value == 0.0 {
+        0
+    } else if value.is_nan() {
+        f64::NAN.to_bits() as i64
+    } else {
+        value.to_bits() as i64
+    }
+}
+
+/// A column that is ready to hash, as [`crate::row_encode`] prepares a column to
+/// encode. Each variant is a Spark type.
+enum Column {
+    Null,
+    /// `boolean`, `tinyint`, `smallint`, `int` and `date`. Spark hashes each one as an
+    /// `int`, and a `boolean` as 1 or 0.
+    Int(Int32Chunked),
+    /// `bigint`, and a `timestamp` in microseconds.
+    Long(Int64Chunked),
+    Float(Float32Chunked),
+    Double(Float64Chunked),
+    /// `string` and `binary`.
+    Bytes(BinaryChunked),
+    Decimal {
+        values: Int128Chunked,
+        precision: usize,
+    },
+    /// An `array`. An Array column also uses this variant, after a cast.
+    List {
+        valid: Option<Vec<bool>>,
+        offsets: Vec<i64>,
+        values: Box<Column>,
+    },
+    Struct {
+        valid: Option<Vec<bool>>,
+        fields: Vec<Column>,
+    },
+}
+
+fn row_validity(s: &Series) -> Option<Vec<bool>> {
+    (s.null_count() > 0).then(|| s.is_not_null().iter().map(|v| v == Some(true)).collect())
+}
+
+fn is_valid(valid: &Option<Vec<bool>>, i: usize) -> bool {
+    valid.as_ref().is_none_or(|valid| valid[i])
+}
+
+impl Column {
+    fn prepare(s: &Series) -> PolarsResult<Self> {
+        let s = s.rechunk();
+        let column = match s.dtype() {
+            DataType::Null => Column::Null,
+            DataType::Boolean | DataType::Int8 | DataType::Int16 | DataType::Int32 => {
+                Column::Int(s.cast(&DataType::Int32)?.i32()?.clone())
+            }
+            DataType::Int64 => Column::Long(s.i64()?.clone()),
+            DataType::Float32 => Column::Float(s.f32()?.clone()),
+            DataType::Float64 => Column::Double(s.f64()?.clone()),
+            DataType::String => Column::Bytes(s.str()?.as_binary()),
+            DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+                Column::Bytes(s.cast(&DataType::String)?.str()?.as_binary())
+            }
+            DataType::Binary => Column::Bytes(s.binary()?.clone()),
+            // A `date` counts the days from 1970-01-01, as a Polars Date does.
+            DataType::Date => Column::Int(s.to_physical_repr().i32()?.clone()),
+            // A `timestamp` and a `timestamp_ntz` count microseconds from 1970-01-01.
+            // A time in nanoseconds is rounded down to its microsecond.
+            DataType::Datetime(unit, _) => {
+                Column::Long(to_micros(s.to_physical_repr().i64()?, unit))
+            }
+            DataType::Decimal(precision, _) => Column::Decimal {
+                values: s.to_physical_repr().i128()?.clone(),
+                precision: *precision,
+            },
+            DataType::List(_) => {
+                let ca = s.list()?;
+                let arr = ca
+                    .downcast_get(0)
+                    .expect("a rechunked column has one chunk");
+                let offsets = arr.offsets();
+                // As in `row_encode`, the values of a slice start at its first offset.
+                let (first, last) = (*offsets.first(), *offsets.last());
+                let values = ca.get_inner().slice(first, (last - first) as usize);
+                Column::List {
+                    valid: row_validity(&s),
+                    offsets: offsets.as_slice().iter().map(|o| o - first).collect(),
+                    values: Box::new(Column::prepare(&values)?),
+                }
+            }
+            DataType::Array(inner, _) => Column::prepare(&s.cast(&DataType::List(inner.clone()))?)?,
+            DataType::Struct(_) => Column::Struct {
+                valid: row_validity(&s),
+                fields: s
+                    .struct_()?
+                    .fields_as_series()
+                    .iter()
+                    .map(Column::prepare)
+                    .collect::<PolarsResult<_>>()?,
+            },
+            dtype => return Err(no_spark_type(dtype)),
+        };
+        Ok(column)
+    }
+
+    /// Gives the hash of the value at row `i`, with `seed` as the hash before it. A
+    /// null gives `seed`, because Spark skips it.
+    fn hash(&self, i: usize, seed: u64, f: Function) -> u64 {
+        match self {
+            Column::Null => seed,
+            Column::Int(ca) => ca.get(i).map_or(seed, |v| f.int(v, seed)),
+            Column::Long(ca) => ca.get(i).map_or(seed, |v| f.long(v, seed)),
+            Column::Float(ca) => ca.get(i).map_or(seed, |v| f.int(float_bits(v), seed)),
+            Column::Double(ca) => ca.get(i).map_or(seed, |v| f.long(double_bits(v), seed)),
+            Column::Bytes(ca) => ca.get(i).map_or(seed, |v| f.bytes(v, seed)),
+            Column::Decimal { values, precision } => values.get(i).map_or(seed, |v| {
+                if *precision <= MAX_LONG_DIGITS {
+                    f.long(v as i64, seed)
+                } else {
+                    f.bytes(&big_integer_bytes(v), seed)
+                }
+            }),
+            Column::List {
+                valid,
+                offsets,
+                values,
+            } => {
+                if !is_valid(valid, i) {
+                    return seed;
+                }
+                let (start, end) = (offsets[i] as usize, offsets[i + 1] as usize);
+                (start..end).fold(seed, |hash, element| values.hash(element, hash, f))
+            }
+            Column::Struct { valid, fields } => {
+                if !is_valid(valid, i) {
+                    return seed;
+                }
+                fields
+                    .iter()
+                    .fold(seed, |hash, field| field.hash(i, hash, f))
+            }
+        }
+    }
+}
+
+/// Checks that the columns of the Struct `dtype` have a Spark type, when polars builds
+/// the plan.
+pub fn check_spark_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
+        DataType::List(inner) | DataType::Array(inner, _) => check_spark_dtype(inner),
+        DataType::Struct(fields) => fields
+            .iter()
+            .try_for_each(|field| check_spark_dtype(field.dtype())),
+        DataType::Unknown(_)
+        | DataType::Null
+        | DataType::Boolean
+        | DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::String
+        | DataType::Categorical(_, _)
+        | DataType::Enum(_, _)
+        | DataType::Binary
+        | DataType::Date
+        | DataType::Datetime(_, _)
+        | DataType::Decimal(_, _) => Ok(()),
+        dtype => Err(no_spark_type(dtype)),
+    }
+}
+
+fn no_spark_type(dtype: &DataType) -> PolarsError {
+    polars_err!(
+        InvalidOperation:
+        "expected `String`, `Binary`, a signed integer, a float, `Boolean`, `Date`, \
+        `Datetime`, `Decimal` or categorical input, or a `List`, `Array` or `Struct` of \
+        them, for a Spark hash, got `{}`",
+        dtype
+    )
+}
+
+/// Gives the hash of each row of the Struct `s`, as `f` of Spark gives it for the
+/// fields of the Struct as its columns. A row of nulls gives the seed 42.
+pub fn row_hashes(s: &Series, f: Function) -> PolarsResult<Vec<u64>> {
+    let column = Column::prepare(s)?;
+    Ok((0..s.len()).map(|i| column.hash(i, SEED, f)).collect())
+}
@@ -1,4 +1,4 @@
-use crate::shared::string_struct;
+use crate::shared::{or_null, string_struct, Rows};
 use polars::chunked_array::ops::arity::{try_unary_elementwise, unary_elementwise};
 use polars::prelude::*;
 use timeharsh::timehash;
@@ -49,6 +49,7 @@ pub fn hash_column(s: &Series) -> PolarsResult<Series> {
 /// epoch seconds as-is. Float32 is refused, not widened: near 1.5e9 it spaces
 /// values 128 seconds apart, far wider than the 3.8 second window at precision 10.
 pub fn epoch_seconds(s: &Series) -> PolarsResult<Float64Chunked> {
+    check_epoch_dtype(s.dtype())?;
     match s.dtype() {
         // Same reasoning as `hash_column`: all-null data must not depend on inference.
         DataType::Null => Ok(Float64Chunked::full_null(s.name().clone(), s.len())),
@@ -70,13 +71,26 @@ pub fn epoch_seconds(s: &Series) -> PolarsResult<Float64Chunked> {
         DataType::Date => Ok(unary_elementwise(s.date()?.physical(), |v| {
             v.map(|v| v as f64 * 86_400.0)
         })),
+        // The check above leaves Float64 and the integers.
+        _ => Ok(s.cast(&DataType::Float64)?.f64()?.clone()),
+    }
+}
+
+/// Checks that [`epoch_seconds`] reads a column of `dtype`. `thash_encode` also calls
+/// this from its output type function, and therefore a plan fails before it reads data.
+pub fn check_epoch_dtype(dtype: &DataType) -> PolarsResult<()> {
+    match dtype {
         DataType::Float32 => {
             polars_bail!(
                 InvalidOperation:
                 "Float32 cannot hold epoch seconds precisely enough for timehash, cast to Float64"
             )
         }
-        DataType::Float64
+        DataType::Null
+        | DataType::Unknown(_)
+        | DataType::Datetime(_, _)
+        | DataType::Date
+        | DataType::Float64
         | DataType::Int8
         | DataType::Int16
         | DataType::Int32
@@ -84,7 +98,7 @@ pub fn epoch_seconds(s: &Series) -> PolarsResult<Float64Chunked> {
         | DataType::UInt8
         | DataType::UInt16
         | DataType::UInt32
-        | DataType::UInt64 => Ok(s.cast(&DataType::Float64)?.f64()?.clone()),
+        | DataType::UInt64 => Ok(()),
         dtype => {
             polars_bail!(
                 InvalidOperation:
@@ -144,17 +158,16 @@ pub fn timehash_encoder(
 
 /// Decodes to the midpoint of the hashed window. The hash holds an instant, not a
 /// wall clock, so the output is UTC and the caller converts to their own zone.
-pub fn timehash_decoder(ca: &StringChunked) -> PolarsResult<Series> {
+pub fn timehash_decoder(ca: &StringChunked, strict: bool) -> PolarsResult<Series> {
+    let decode = |value: &str| -> PolarsResult<i64> {
+        validate_timehash(value)?;
+        let seconds = timehash::decode(value).map_err(|e| PolarsError::ComputeError(e.into()))?;
+        Ok((seconds * 1e6).round() as i64)
+    };
+    let mut rows = Rows::of(ca.name());
     let out: Int64Chunked = try_unary_elementwise(ca, |value| -> PolarsResult<Option<i64>> {
-        match value {
-            Some(value) => {
-                validate_timehash(value)?;
-                let seconds =
-                    timehash::decode(value).map_err(|e| PolarsError::ComputeError(e.into()))?;
-                Ok(Some((seconds * 1e6).round() as i64))
-            }
-            _ => Ok(None),
-        }
+        let decoded = rows.next(value.map(decode).transpose());
+        Ok(or_null(decoded, strict)?.flatten())
     })?;
 
     out.into_series().cast(&DataType::Datetime(
@@ -163,8 +176,8 @@ pub fn timehash_decoder(ca: &StringChunked) -> PolarsResult<Series> {
     ))
 }
 
-pub fn timehash_neighbors(ca: &StringChunked) -> PolarsResult<StructChunked> {
-    string_struct(ca, ["before", "after"], |value| {
+pub fn timehash_neighbors(ca: &StringChunked, strict: bool) -> PolarsResult<StructChunked> {
+    string_struct(ca, ["before", "after"], strict, |value| {
         validate_timehash(value)?;
         let (before, after) =
             timehash::neighbors(value).map_err(|e| PolarsError::ComputeError(e.into()))?;
@@ -1,3 +1,4 @@
+use crate::registry::{Algorithm, Hasher, Seed};
 use xxhash_rust::xxh3::xxh3_128_with_seed;
 use xxhash_rust::xxh3::xxh3_64_with_seed;
 use xxhash_rust::xxh32::xxh32;
@@ -18,3 +19,26 @@ pub fn xxhash3_64(value: &[u8], seed: u64) -> u64 {
 pub fn xxhash3_128(value: &[u8], seed: u64) -> u128 {
     xxh3_128_with_seed(value, seed)
 }
+
+pub static ALGORITHMS: &[Algorithm] = &[
+    Algorithm {
+        name: "xxhash32",
+        seed: Seed::U32,
+        hasher: Hasher::U32(|v, seed| xxhash_32(v, seed as u32)),
+    },
+    Algorithm {
+        name: "xxhash64",
+        seed: Seed::U64,
+        hasher: Hasher::U64(xxhash_64),
+    },
+    Algorithm {
+        name: "xxh3_64",
+        seed: Seed::U64,
+        hasher: Hasher::U64(xxhash3_64),
+    },
+    Algorithm {
+        name: "xxh3_128",
+        seed: Seed::U64,
+        hasher: Hasher::U128(xxhash3_128),
+    },
+];